@@ -0,0 +1,140 @@
+//! Benchmarks of the bridge hot paths at VGA, 720p, 1080p and 4K, measured with
+//! criterion and reported with the throughput over the image data.
+//!
+//! ```bash
+//! # Run every benchmark, or only those whose name matches a filter
+//! cargo bench --bench bridge
+//! cargo bench --bench bridge -- from_imgmsg/bgr8
+//! # Pure-Rust benchmarks only, without a system OpenCV install
+//! cargo bench --no-default-features --bench bridge
+//! # Save a named baseline, then compare a later run against it
+//! cargo bench --bench bridge -- --save-baseline main
+//! cargo bench --bench bridge -- --baseline main
+//! ```
+
+use criterion::{
+    BatchSize,
+    BenchmarkId,
+    Criterion,
+    Throughput,
+    criterion_group,
+    criterion_main,
+};
+
+use cv_bridge::{
+    CvImage,
+    msgs::sensor_msgs::Image,
+    utils::image_byteorder_ops,
+};
+
+const RESOLUTIONS: &[(&str, u32, u32)] = &[
+    ("vga", 640, 480),
+    ("720p", 1280, 720),
+    ("1080p", 1920, 1080),
+    ("4k", 3840, 2160),
+];
+
+/// Returns an image message with deterministic pseudo-random data
+fn image(width: u32, height: u32, encoding: &str, bytes_per_pixel: u32, is_bigendian: u8) -> Image {
+    let step = width * bytes_per_pixel;
+
+    Image {
+        height,
+        width,
+        encoding: encoding.to_string(),
+        is_bigendian,
+        step,
+        data: (0..step * height).map(|i| (i.wrapping_mul(2654435761) >> 24) as u8).collect(),
+        ..Default::default()
+    }
+}
+
+fn bench_messages(c: &mut Criterion) {
+    let mut from_group = c.benchmark_group("from_imgmsg");
+    for (resolution, width, height) in RESOLUTIONS {
+        for (case, message) in [
+            ("bgr8", image(*width, *height, "bgr8", 3, 0)),
+            ("mono16_be", image(*width, *height, "mono16", 2, 1)),
+            ("32FC1", image(*width, *height, "32FC1", 4, 0)),
+        ] {
+            from_group.throughput(Throughput::Bytes(message.data.len() as u64));
+            from_group.bench_function(BenchmarkId::new(case, resolution), |b| b.iter_batched(
+                || message.clone(),
+                |message| CvImage::from_imgmsg(message).unwrap(),
+                BatchSize::LargeInput,
+            ));
+        }
+    }
+    from_group.finish();
+
+    let mut into_group = c.benchmark_group("into_imgmsg");
+    for (resolution, width, height) in RESOLUTIONS {
+        for (case, message) in [
+            ("bgr8", image(*width, *height, "bgr8", 3, 0)),
+            ("mono16_be", image(*width, *height, "mono16", 2, 1)),
+            ("32FC1", image(*width, *height, "32FC1", 4, 0)),
+        ] {
+            let is_bigendian = message.is_bigendian;
+            into_group.throughput(Throughput::Bytes(message.data.len() as u64));
+            let cv_image = CvImage::from_imgmsg(message).unwrap();
+            into_group.bench_function(BenchmarkId::new(case, resolution), |b| b.iter_batched(
+                || cv_image.clone(),
+                |cv_image| cv_image.into_imgmsg::<Image>(is_bigendian).unwrap(),
+                BatchSize::LargeInput,
+            ));
+        }
+    }
+    into_group.finish();
+}
+
+fn bench_byteorder(c: &mut Criterion) {
+    let mut group = c.benchmark_group("image_byteorder_ops");
+    for (resolution, width, height) in RESOLUTIONS {
+        let data16 = image(*width, *height, "mono16", 2, 0).data;
+        let data32 = image(*width, *height, "32FC1", 4, 0).data;
+        let u16s = image_byteorder_ops::from_u8_to_u16(&data16, false);
+        let f32s = image_byteorder_ops::from_u8_to_f32(&data32, false);
+
+        group.throughput(Throughput::Bytes(data16.len() as u64));
+        group.bench_function(BenchmarkId::new("from_be_to_le", resolution), |b| b.iter(|| image_byteorder_ops::from_be_to_le(&data16)));
+        group.bench_function(BenchmarkId::new("from_le_to_be", resolution), |b| b.iter(|| image_byteorder_ops::from_le_to_be(&data16)));
+        group.bench_function(BenchmarkId::new("from_u8_to_u16", resolution), |b| b.iter(|| image_byteorder_ops::from_u8_to_u16(&data16, true)));
+        group.bench_function(BenchmarkId::new("from_u16_to_u8", resolution), |b| b.iter(|| image_byteorder_ops::from_u16_to_u8(&u16s, true)));
+
+        group.throughput(Throughput::Bytes(data32.len() as u64));
+        group.bench_function(BenchmarkId::new("from_u8_to_f32", resolution), |b| b.iter(|| image_byteorder_ops::from_u8_to_f32(&data32, true)));
+        group.bench_function(BenchmarkId::new("from_f32_to_u8", resolution), |b| b.iter(|| image_byteorder_ops::from_f32_to_u8(&f32s, true)));
+    }
+    group.finish();
+}
+
+#[cfg(feature = "opencv")]
+fn bench_opencv(c: &mut Criterion) {
+    let mut as_cvmat_group = c.benchmark_group("as_cvmat");
+    for (resolution, width, height) in RESOLUTIONS {
+        let mut bgr8 = CvImage::from_imgmsg(image(*width, *height, "bgr8", 3, 0)).unwrap();
+        as_cvmat_group.throughput(Throughput::Bytes(*width as u64 * *height as u64 * 3));
+        as_cvmat_group.bench_function(BenchmarkId::new("bgr8", resolution), |b| b.iter(|| bgr8.as_cvmat().unwrap()));
+    }
+    as_cvmat_group.finish();
+
+    let mut to_cvimage_group = c.benchmark_group("to_cvimage");
+    for (resolution, width, height) in RESOLUTIONS {
+        for (case, message, encoding) in [
+            ("bgr8_to_mono8", image(*width, *height, "bgr8", 3, 0), "mono8"),
+            ("bayer_to_bgr8", image(*width, *height, "bayer_rggb8", 1, 0), "bgr8"),
+            ("mono16_to_mono8", image(*width, *height, "mono16", 2, 0), "mono8"),
+        ] {
+            to_cvimage_group.throughput(Throughput::Bytes(message.data.len() as u64));
+            let mut source = CvImage::from_imgmsg(message).unwrap();
+            to_cvimage_group.bench_function(BenchmarkId::new(case, resolution), |b| b.iter(|| source.to_cvimage(encoding).unwrap()));
+        }
+    }
+    to_cvimage_group.finish();
+}
+
+#[cfg(feature = "opencv")]
+criterion_group!(benches, bench_messages, bench_byteorder, bench_opencv);
+#[cfg(not(feature = "opencv"))]
+criterion_group!(benches, bench_messages, bench_byteorder);
+criterion_main!(benches);
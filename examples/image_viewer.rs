@@ -1,6 +1,7 @@
 use opencv::highgui;
 use cv_bridge::{
     CvImage,
+    display::DisplayOptions,
     msgs::sensor_msgs::Image,
 };
 
@@ -15,7 +16,8 @@ fn main() {
         move |image: Image| {
             // Convert ros Image to opencv Mat
             let mut cv_image = CvImage::from_imgmsg(image).expect("failed to construct CvImage from ros Image"); 
-            let mat = cv_image.as_cvmat().expect("failed to convert CvImage to Mat");
+            let mut display_image = cv_image.to_display_image(&DisplayOptions::default()).expect("failed to convert CvImage for display");
+            let mat = display_image.as_cvmat().expect("failed to convert CvImage to Mat");
 
             // Display image
             let window = "view";
@@ -0,0 +1,36 @@
+//! Runs every function of `image_byteorder_ops` on arbitrary data and checks that
+//! the conversions round trip. The first byte selects the byte order.
+#![no_main]
+
+use cv_bridge::utils::image_byteorder_ops::{
+    from_be_to_le,
+    from_f32_to_u8,
+    from_le_to_be,
+    from_u16_to_u8,
+    from_u8_to_f32,
+    from_u8_to_u16,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    let (big_endian, data) = match bytes.split_first() {
+        Some((first, data)) => (first & 1 == 1, data),
+        None => return,
+    };
+
+    // Trailing bytes which do not make a whole value are dropped
+    let even = &data[..data.len() / 2 * 2];
+    let quad = &data[..data.len() / 4 * 4];
+
+    let u16s = from_u8_to_u16(data, big_endian);
+    assert_eq!(u16s.len(), data.len() / 2);
+    assert_eq!(from_u16_to_u8(&u16s, big_endian), even);
+
+    let f32s = from_u8_to_f32(data, big_endian);
+    assert_eq!(f32s.len(), data.len() / 4);
+    assert_eq!(from_f32_to_u8(&f32s, big_endian), quad);
+
+    let le = from_be_to_le(data);
+    assert_eq!(le.len(), even.len());
+    assert_eq!(from_le_to_be(&le), even);
+});
@@ -0,0 +1,38 @@
+//! Deserializes arbitrary bytes as CDR `sensor_msgs/Image`, `sensor_msgs/CompressedImage`
+//! and `sensor_msgs/CameraInfo` messages. Messages which deserialize must serialize
+//! back to bytes which deserialize to the same message.
+//!
+//! The crate does not decode the compressed payload of `CompressedImage` messages,
+//! so CDR deserialization is the entry point of compressed images.
+#![no_main]
+
+use cv_bridge::{
+    CvImage,
+    msgs::{
+        cdr::CdrMessage,
+        sensor_msgs::{
+            CameraInfo,
+            CompressedImage,
+            Image,
+        },
+    },
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|bytes: &[u8]| {
+    if let Ok(image) = Image::from_cdr(bytes) {
+        assert_eq!(Image::from_cdr(&image.to_cdr().unwrap()).unwrap(), image);
+    }
+
+    if let Ok(compressed) = CompressedImage::from_cdr(bytes) {
+        assert_eq!(CompressedImage::from_cdr(&compressed.to_cdr().unwrap()).unwrap(), compressed);
+    }
+
+    if let Ok(info) = CameraInfo::from_cdr(bytes) {
+        // NaN coefficients do not compare equal, so compare the serialized bytes
+        let bytes = info.to_cdr().unwrap();
+        assert_eq!(CameraInfo::from_cdr(&bytes).unwrap().to_cdr().unwrap(), bytes);
+    }
+
+    let _ = CvImage::from_cdr(bytes);
+});
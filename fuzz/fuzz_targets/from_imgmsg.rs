@@ -0,0 +1,62 @@
+//! Builds `sensor_msgs::Image` messages from arbitrary bytes and converts them with
+//! `CvImage::from_imgmsg`, `CvImage::as_cvmat` and `CvImage::into_imgmsg`.
+//!
+//! ## Input layout
+//! * `[0]` - Index into `ENCODINGS`
+//! * `[1]` - `is_bigendian`
+//! * `[2..4]` - `width` as little-endian u16
+//! * `[4..6]` - `height` as little-endian u16
+//! * `[6..10]` - `step` as little-endian u32
+//! * `[10..]` - `data`
+#![no_main]
+
+use cv_bridge::{
+    CvImage,
+    msgs::sensor_msgs::Image,
+    utils::image_validation::validate_imgmsg,
+};
+use libfuzzer_sys::fuzz_target;
+use opencv::prelude::*;
+
+/// Encodings of the `image_encodings` module and an unknown one
+const ENCODINGS: &[&str] = &[
+    "mono8", "rgb8", "rgba8", "bgr8", "bgra8",
+    "mono16", "rgb16", "rgba16", "bgr16", "bgra16",
+    "bayer_rggb8", "bayer_bggr8", "bayer_gbrg8", "bayer_grbg8",
+    "bayer_rggb16", "bayer_bggr16", "bayer_gbrg16", "bayer_grbg16",
+    "yuv422", "yuv422_yuy2",
+    "8UC1", "8UC2", "8UC3", "8UC4",
+    "16UC1", "16UC2", "16UC3", "16UC4",
+    "32FC1", "32FC2", "32FC3", "32FC4",
+    "64FC1",
+];
+
+fuzz_target!(|bytes: &[u8]| {
+    if bytes.len() < 10 {
+        return;
+    }
+
+    let image = Image {
+        encoding: ENCODINGS[bytes[0] as usize % ENCODINGS.len()].to_string(),
+        is_bigendian: bytes[1],
+        width: u16::from_le_bytes([bytes[2], bytes[3]]) as u32,
+        height: u16::from_le_bytes([bytes[4], bytes[5]]) as u32,
+        step: u32::from_le_bytes([bytes[6], bytes[7], bytes[8], bytes[9]]),
+        data: bytes[10..].to_vec(),
+        ..Default::default()
+    };
+    let (width, height) = (image.width, image.height);
+
+    let mut cv_image = match CvImage::from_imgmsg(image) {
+        Ok(cv_image) => cv_image,
+        Err(_) => return,
+    };
+    assert_eq!((cv_image.width(), cv_image.height()), (width as usize, height as usize));
+
+    if let Ok(mat) = cv_image.as_cvmat() {
+        assert_eq!((mat.cols(), mat.rows()), (width as i32, height as i32));
+    }
+
+    let message: Image = cv_image.into_imgmsg(0).expect("a valid CvImage converts back to a message");
+    assert!(validate_imgmsg(&message).is_ok());
+});
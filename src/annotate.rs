@@ -0,0 +1,301 @@
+//! annotate module draws bounding boxes, keypoints, polylines, text, masks and
+//! banners onto images for `debug_image` topics. This module is only available
+//! with the `opencv` feature.
+//!
+//! The image is converted to `bgr8` once, with `CvImage::to_display_image`, when
+//! the `Annotator` is created, and every drawing call then works in place on
+//! that copy, so the source image can have any supported encoding.
+//!
+//! # Example
+//! ```ignore
+//! let mut annotator = cv_image.annotate().unwrap();
+//! annotator
+//!     .bounding_box((120, 80, 64, 128), Some("person 0.92"), [0, 255, 0]).unwrap()
+//!     .circle((152, 100), 3, [0, 0, 255]).unwrap()
+//!     .stamp_banner(Some(29.8)).unwrap();
+//! let debug_image: Image = annotator.into_imgmsg().unwrap();
+//! ```
+
+use opencv::{
+    core::{
+        Point,
+        Rect,
+        Scalar,
+        Vector,
+    },
+    imgproc,
+};
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::display::{
+    DisplayOptions,
+    DisplayRange,
+};
+use crate::image_message::ImageMessage;
+use crate::utils::image_encodings;
+
+const FONT_FACE: i32 = imgproc::FONT_HERSHEY_SIMPLEX;
+const BANNER_MARGIN: i32 = 4;
+
+/// Draws annotations onto a `bgr8` copy of an image
+#[derive(Debug, Clone)]
+pub struct Annotator {
+    image: CvImage,
+    thickness: i32,
+    font_scale: f64,
+    banner_height: i32,
+}
+
+impl CvImage {
+    /// Returns an `Annotator` drawing onto a `bgr8` copy of the image. 8 and 16-bit
+    /// images are scaled over the full range of their type, so the annotated image
+    /// shows their true values, and floating point images over their valid values.
+    ///
+    /// ## Returns
+    /// * `Annotator` object
+    pub fn annotate(&mut self) -> Result<Annotator, Box<dyn Error>> {
+        let range = match image_encodings::get_bit_depth(self.encoding()) {
+            8 | 16 => DisplayRange::Encoding,
+            _ => DisplayRange::Auto,
+        };
+
+        self.annotate_with(&DisplayOptions {
+            range,
+            ..Default::default()
+        })
+    }
+
+    /// Returns an `Annotator` drawing onto a `bgr8` copy of the image.
+    ///
+    /// ## Arguments
+    /// * `options` - Conversion of single channel images, such as depth images, to `bgr8`
+    ///
+    /// ## Returns
+    /// * `Annotator` object
+    pub fn annotate_with(&mut self, options: &DisplayOptions) -> Result<Annotator, Box<dyn Error>> {
+        Ok(Annotator {
+            image: self.to_display_image(options)?,
+            thickness: 2,
+            font_scale: 0.5,
+            banner_height: 0,
+        })
+    }
+}
+
+impl Annotator {
+    /// Sets the line thickness and font scale of the following annotations.
+    /// The defaults are 2 pixels and 0.5.
+    ///
+    /// ## Arguments
+    /// * `thickness` - Line thickness in pixels
+    /// * `font_scale` - Scale of the Hershey font
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn set_style(&mut self, thickness: i32, font_scale: f64) -> &mut Self {
+        self.thickness = thickness;
+        self.font_scale = font_scale;
+        self
+    }
+
+    /// Draws a bounding box, with an optional label on a filled background above it.
+    ///
+    /// ## Arguments
+    /// * `rect` - `(x, y, width, height)` of the box in pixels
+    /// * `label` - Text drawn at the top left corner of the box
+    /// * `color` - `[b, g, r]` color of the box
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn bounding_box(&mut self, rect: (i32, i32, i32, i32), label: Option<&str>, color: [u8; 3]) -> Result<&mut Self, Box<dyn Error>> {
+        let (x, y, width, height) = rect;
+        let mut mat = self.image.as_cvmat()?;
+
+        imgproc::rectangle(&mut mat, Rect::new(x, y, width, height), scalar(color), self.thickness, imgproc::LINE_8, 0)?;
+
+        if let Some(label) = label {
+            let mut baseline = 0;
+            let size = imgproc::get_text_size(label, FONT_FACE, self.font_scale, 1, &mut baseline)?;
+            let top = (y - size.height - baseline - BANNER_MARGIN).max(0);
+            let background = Rect::new(x, top, size.width + 2 * BANNER_MARGIN, size.height + baseline + BANNER_MARGIN);
+
+            imgproc::rectangle(&mut mat, background, scalar(color), imgproc::FILLED, imgproc::LINE_8, 0)?;
+            imgproc::put_text(
+                &mut mat, label, Point::new(x + BANNER_MARGIN, top + size.height + BANNER_MARGIN / 2),
+                FONT_FACE, self.font_scale, scalar(contrast_color(color)), 1, imgproc::LINE_AA, false
+            )?;
+        }
+
+        Ok(self)
+    }
+
+    /// Draws a polyline, for example a contour or a skeleton limb.
+    ///
+    /// ## Arguments
+    /// * `points` - `(x, y)` vertices in pixels
+    /// * `closed` - Whether the last vertex is joined to the first one
+    /// * `color` - `[b, g, r]` color of the lines
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn polyline(&mut self, points: &[(i32, i32)], closed: bool, color: [u8; 3]) -> Result<&mut Self, Box<dyn Error>> {
+        let mut mat = self.image.as_cvmat()?;
+        let points: Vector<Point> = points.iter().map(|(x, y)| Point::new(*x, *y)).collect();
+
+        imgproc::polylines(&mut mat, &points, closed, scalar(color), self.thickness, imgproc::LINE_AA, 0)?;
+
+        Ok(self)
+    }
+
+    /// Draws a filled circle, for example a keypoint.
+    ///
+    /// ## Arguments
+    /// * `center` - `(x, y)` center in pixels
+    /// * `radius` - Radius in pixels
+    /// * `color` - `[b, g, r]` color of the circle
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn circle(&mut self, center: (i32, i32), radius: i32, color: [u8; 3]) -> Result<&mut Self, Box<dyn Error>> {
+        let mut mat = self.image.as_cvmat()?;
+
+        imgproc::circle(&mut mat, Point::new(center.0, center.1), radius, scalar(color), imgproc::FILLED, imgproc::LINE_AA, 0)?;
+
+        Ok(self)
+    }
+
+    /// Draws text with its bottom left corner at the given position.
+    ///
+    /// ## Arguments
+    /// * `position` - `(x, y)` of the bottom left corner of the text in pixels
+    /// * `text` - Text to draw
+    /// * `color` - `[b, g, r]` color of the text
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn text(&mut self, position: (i32, i32), text: &str, color: [u8; 3]) -> Result<&mut Self, Box<dyn Error>> {
+        let mut mat = self.image.as_cvmat()?;
+
+        imgproc::put_text(
+            &mut mat, text, Point::new(position.0, position.1),
+            FONT_FACE, self.font_scale, scalar(color), 1, imgproc::LINE_AA, false
+        )?;
+
+        Ok(self)
+    }
+
+    /// Blends a color over the pixels of a mask, for example an instance mask.
+    ///
+    /// ## Arguments
+    /// * `mask` - `mono8` or `8UC1` image of the same size, non-zero inside the mask
+    /// * `color` - `[b, g, r]` color of the mask
+    /// * `alpha` - Opacity of the color, from 0 to 1
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn mask(&mut self, mask: &CvImage, color: [u8; 3], alpha: f64) -> Result<&mut Self, Box<dyn Error>> {
+        if mask.width() != self.image.width() || mask.height() != self.image.height() {
+            Err(format!(
+                "Mask and image have different sizes [mask: {}x{}, image: {}x{}]",
+                mask.width(), mask.height(), self.image.width(), self.image.height()
+            ))?
+        }
+        if !(0.0..=1.0).contains(&alpha) {
+            Err(format!("Alpha must be between 0 and 1 [alpha: {}]", alpha))?
+        }
+
+        let mask_data = match (mask.encoding().as_str(), mask.as_container()) {
+            ("mono8" | "8UC1", DataContainer::VecU8(data)) => data,
+            _ => Err(format!("Unsupported encoding for mask [encoding: {}]", mask.encoding()))?
+        };
+        let data = match self.image.as_mut_container() {
+            DataContainer::VecU8(data) => data,
+            _ => Err("Unsupported container type".to_string())?
+        };
+
+        for (pixel, inside) in data.chunks_exact_mut(3).zip(mask_data) {
+            if *inside != 0 {
+                for c in 0..3 {
+                    pixel[c] = (alpha * color[c] as f64 + (1.0 - alpha) * pixel[c] as f64).round() as u8;
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Draws a line of white text on a black strip across the top of the image.
+    /// Successive banners are stacked below each other.
+    ///
+    /// ## Arguments
+    /// * `text` - Text of the banner
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn banner(&mut self, text: &str) -> Result<&mut Self, Box<dyn Error>> {
+        let mut mat = self.image.as_cvmat()?;
+        let mut baseline = 0;
+        let size = imgproc::get_text_size(text, FONT_FACE, self.font_scale, 1, &mut baseline)?;
+        let height = size.height + baseline + 2 * BANNER_MARGIN;
+        let strip = Rect::new(0, self.banner_height, self.image.width() as i32, height);
+
+        imgproc::rectangle(&mut mat, strip, Scalar::all(0.0), imgproc::FILLED, imgproc::LINE_8, 0)?;
+        imgproc::put_text(
+            &mut mat, text, Point::new(BANNER_MARGIN, self.banner_height + BANNER_MARGIN + size.height),
+            FONT_FACE, self.font_scale, Scalar::all(255.0), 1, imgproc::LINE_AA, false
+        )?;
+        self.banner_height += height;
+
+        Ok(self)
+    }
+
+    /// Draws a banner with the stamp of the image header and, optionally, a frame rate.
+    ///
+    /// ## Arguments
+    /// * `fps` - Frame rate, in frames per second, to show after the stamp
+    ///
+    /// ## Returns
+    /// * The `Annotator` for chaining
+    pub fn stamp_banner(&mut self, fps: Option<f64>) -> Result<&mut Self, Box<dyn Error>> {
+        let stamp = &self.image.header().stamp;
+        let text = match fps {
+            Some(fps) => format!("{}.{:09}  {:.1} FPS", stamp.sec, stamp.nsec, fps),
+            None => format!("{}.{:09}", stamp.sec, stamp.nsec),
+        };
+
+        self.banner(&text)
+    }
+
+    /// Returns the annotated `bgr8` image, with the header of the source image.
+    ///
+    /// ## Returns
+    /// * `CvImage` object
+    pub fn into_cvimage(self) -> CvImage {
+        self.image
+    }
+
+    /// Returns the annotated `bgr8` image as a message, with the header of the source image.
+    ///
+    /// ## Returns
+    /// * ros `Image` message
+    pub fn into_imgmsg<T: ImageMessage>(self) -> Result<T, Box<dyn Error>> {
+        self.image.into_imgmsg(0)
+    }
+}
+
+fn scalar(color: [u8; 3]) -> Scalar {
+    Scalar::new(color[0] as f64, color[1] as f64, color[2] as f64, 0.0)
+}
+
+/// Returns black or white, whichever is more readable over the given color
+fn contrast_color(color: [u8; 3]) -> [u8; 3] {
+    let luma = 0.114 * color[0] as f64 + 0.587 * color[1] as f64 + 0.299 * color[2] as f64;
+    match luma > 128.0 {
+        true => [0, 0, 0],
+        false => [255, 255, 255],
+    }
+}
@@ -0,0 +1,174 @@
+//! ini module reads and writes calibration files in the INI format of the
+//! `camera_calibration_parsers` package.
+//!
+//! # Format
+//! ```text
+//! # Camera intrinsics
+//!
+//! [image]
+//!
+//! width
+//! 640
+//!
+//! height
+//! 480
+//!
+//! [narrow_stereo]
+//!
+//! camera matrix
+//! 430.15 0 311.71
+//! 0 430.53 221.07
+//! 0 0 1
+//!
+//! distortion
+//! -0.337 0.117 0.001 -0.0002 0
+//!
+//! rectification
+//! 1 0 0
+//! 0 1 0
+//! 0 0 1
+//!
+//! projection
+//! 357.43 0 306.91 0
+//! 0 362.73 222.09 0
+//! 0 0 1 0
+//! ```
+//!
+//! The format does not store the distortion model, so it is set to
+//! `rational_polynomial` when there are 8 distortion coefficients and
+//! `plumb_bob` otherwise.
+
+use std::error::Error;
+use std::fmt::Write;
+
+use crate::msgs::sensor_msgs::CameraInfo;
+use super::{
+    default_distortion_model,
+    into_array,
+};
+
+const KEYS: [&str; 6] = ["width", "height", "camera matrix", "distortion", "rectification", "projection"];
+
+/// Parses a calibration in the INI format.
+///
+/// ## Arguments
+/// * `buffer` - Content of the calibration file
+///
+/// ## Returns
+/// * `(camera_name, camera_info)` tuple or an error if the calibration is malformed
+pub fn parse_calibration(buffer: &str) -> Result<(String, CameraInfo), Box<dyn Error>> {
+    let mut camera_name = None;
+    let mut section = None;
+    // (key, values) in the order they appear in the file
+    let mut entries: Vec<(&str, Vec<f64>)> = Vec::new();
+
+    for (line_number, line) in buffer.lines().enumerate() {
+        let line = match line.find(['#', ';']) {
+            Some(index) => &line[..index],
+            None => line,
+        }.trim();
+
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            let name = name.trim();
+            if name != "image" {
+                camera_name = Some(name.to_string());
+            }
+            section = Some(name.to_string());
+            continue;
+        }
+
+        if section.is_none() {
+            Err(format!("Entry outside of a section [line: {}]", line_number + 1))?
+        }
+
+        if let Some(key) = KEYS.iter().find(|key| **key == line) {
+            entries.push((key, Vec::new()));
+            continue;
+        }
+
+        let values = match entries.last_mut() {
+            Some((_, values)) => values,
+            None => Err(format!("Value without a key [line: {}]", line_number + 1))?
+        };
+
+        for token in line.split_whitespace() {
+            match token.parse::<f64>() {
+                Ok(value) => values.push(value),
+                Err(_) => Err(format!("Invalid number [line: {}, token: {}]", line_number + 1, token))?
+            }
+        }
+    }
+
+    let find = |key: &str| -> Result<&[f64], Box<dyn Error>> {
+        match entries.iter().find(|(entry_key, _)| *entry_key == key) {
+            Some((_, values)) => Ok(values),
+            None => Err(format!("Missing key in calibration [key: {}]", key))?
+        }
+    };
+    let dimension = |key: &str| -> Result<u32, Box<dyn Error>> {
+        match find(key)? {
+            [value] if *value >= 0.0 && value.fract() == 0.0 => Ok(*value as u32),
+            values => Err(format!("Invalid image {} [values: {:?}]", key, values))?
+        }
+    };
+
+    let d = find("distortion")?.to_vec();
+    let camera_info = CameraInfo {
+        width: dimension("width")?,
+        height: dimension("height")?,
+        distortion_model: default_distortion_model(d.len()).to_string(),
+        K: into_array("camera matrix", find("camera matrix")?)?,
+        R: into_array("rectification", find("rectification")?)?,
+        P: into_array("projection", find("projection")?)?,
+        D: d,
+        ..Default::default()
+    };
+
+    Ok((camera_name.unwrap_or_default(), camera_info))
+}
+
+/// Formats a calibration in the INI format.
+///
+/// ## Arguments
+/// * `camera_name` - Name of the camera stored in the file
+/// * `camera_info` - `sensor_msgs::CameraInfo` message containing the calibration
+///
+/// ## Returns
+/// * Content of the calibration file or an error if the camera name is not a valid section name
+pub fn format_calibration(camera_name: &str, camera_info: &CameraInfo) -> Result<String, Box<dyn Error>> {
+    if camera_name.is_empty() || camera_name == "image" || camera_name.contains(['[', ']', '#', ';', '\n']) {
+        Err(format!("Invalid camera name for INI calibration [camera_name: {}]", camera_name))?
+    }
+
+    let mut buffer = String::new();
+
+    writeln!(buffer, "# Camera intrinsics")?;
+    writeln!(buffer)?;
+    writeln!(buffer, "[image]")?;
+    writeln!(buffer)?;
+    writeln!(buffer, "width\n{}", camera_info.width)?;
+    writeln!(buffer)?;
+    writeln!(buffer, "height\n{}", camera_info.height)?;
+    writeln!(buffer)?;
+    writeln!(buffer, "[{}]", camera_name)?;
+    writeln!(buffer)?;
+    write_matrix(&mut buffer, "camera matrix", &camera_info.K, 3)?;
+    write_matrix(&mut buffer, "distortion", &camera_info.D, camera_info.D.len().max(1))?;
+    write_matrix(&mut buffer, "rectification", &camera_info.R, 3)?;
+    write_matrix(&mut buffer, "projection", &camera_info.P, 4)?;
+
+    Ok(buffer)
+}
+
+fn write_matrix(buffer: &mut String, key: &str, values: &[f64], cols: usize) -> std::fmt::Result {
+    writeln!(buffer, "{}", key)?;
+    for row in values.chunks(cols) {
+        let row: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+        writeln!(buffer, "{}", row.join(" "))?;
+    }
+    writeln!(buffer)
+}
@@ -0,0 +1,143 @@
+//! Readers and writers for the camera calibration files used by the
+//! `camera_calibration_parsers` ROS package. The calibration is returned as a
+//! `sensor_msgs::CameraInfo` message together with the camera name stored in the file.
+//! 
+//! ## Supported formats
+//! * YAML (`.yaml`, `.yml`) - Requires the `yaml` feature
+//! * INI (`.ini`)
+//! 
+//! The calibration files do not store the header, binning and region of interest,
+//! so they are left at their default values when reading and ignored when writing.
+
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::msgs::sensor_msgs::CameraInfo;
+
+pub mod ini;
+#[cfg(feature = "yaml")]
+pub mod yaml;
+
+/// Calibration file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationFormat {
+    Yaml,
+    Ini,
+}
+
+impl CalibrationFormat {
+    /// Returns the calibration format for the extension of the given file
+    /// 
+    /// ## Arguments
+    /// * `path` - Path of the calibration file (e.g. "camera.yaml")
+    /// 
+    /// ## Returns
+    /// The calibration format or an error if the extension is not supported
+    pub fn from_path(path: &Path) -> Result<CalibrationFormat, Box<dyn Error>> {
+        let extension = path.extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_lowercase());
+
+        match extension.as_deref() {
+            Some("yaml") | Some("yml") => Ok(CalibrationFormat::Yaml),
+            Some("ini") => Ok(CalibrationFormat::Ini),
+            _ => Err(format!("Unsupported calibration file extension [path: {}]", path.display()))?
+        }
+    }
+}
+
+/// Parses a calibration from a string in the given format.
+/// 
+/// ## Arguments
+/// * `buffer` - Content of the calibration file
+/// * `format` - Format of the calibration file
+/// 
+/// ## Returns
+/// * `(camera_name, camera_info)` tuple or an error if the calibration is malformed
+pub fn parse_calibration(buffer: &str, format: CalibrationFormat) -> Result<(String, CameraInfo), Box<dyn Error>> {
+    match format {
+        #[cfg(feature = "yaml")]
+        CalibrationFormat::Yaml => yaml::parse_calibration(buffer),
+        #[cfg(not(feature = "yaml"))]
+        CalibrationFormat::Yaml => Err("YAML calibration files require the `yaml` feature".to_string())?,
+        CalibrationFormat::Ini => ini::parse_calibration(buffer),
+    }
+}
+
+/// Formats a calibration as a string in the given format.
+/// 
+/// ## Arguments
+/// * `camera_name` - Name of the camera stored in the file
+/// * `camera_info` - `sensor_msgs::CameraInfo` message containing the calibration
+/// * `format` - Format of the calibration file
+/// 
+/// ## Returns
+/// * Content of the calibration file
+pub fn format_calibration(camera_name: &str, camera_info: &CameraInfo, format: CalibrationFormat) -> Result<String, Box<dyn Error>> {
+    match format {
+        #[cfg(feature = "yaml")]
+        CalibrationFormat::Yaml => Ok(yaml::format_calibration(camera_name, camera_info)),
+        #[cfg(not(feature = "yaml"))]
+        CalibrationFormat::Yaml => Err("YAML calibration files require the `yaml` feature".to_string())?,
+        CalibrationFormat::Ini => ini::format_calibration(camera_name, camera_info),
+    }
+}
+
+/// Reads a calibration file. The format is selected by the file extension.
+/// 
+/// # Example
+/// ```ignore
+/// let (camera_name, camera_info) = read_calibration("camera.yaml").unwrap();
+/// ```
+/// 
+/// ## Arguments
+/// * `path` - Path of the calibration file
+/// 
+/// ## Returns
+/// * `(camera_name, camera_info)` tuple or an error if the file could not be read
+pub fn read_calibration<P: AsRef<Path>>(path: P) -> Result<(String, CameraInfo), Box<dyn Error>> {
+    let path = path.as_ref();
+    let format = CalibrationFormat::from_path(path)?;
+    let buffer = fs::read_to_string(path)?;
+
+    parse_calibration(&buffer, format)
+}
+
+/// Writes a calibration file, creating the parent directories if needed. The
+/// format is selected by the file extension.
+/// 
+/// ## Arguments
+/// * `path` - Path of the calibration file
+/// * `camera_name` - Name of the camera stored in the file
+/// * `camera_info` - `sensor_msgs::CameraInfo` message containing the calibration
+pub fn write_calibration<P: AsRef<Path>>(path: P, camera_name: &str, camera_info: &CameraInfo) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref();
+    let format = CalibrationFormat::from_path(path)?;
+    let buffer = format_calibration(camera_name, camera_info, format)?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, buffer)?;
+
+    Ok(())
+}
+
+/// Returns the `distortion_model` for a calibration file which does not store it,
+/// based on the number of distortion coefficients.
+pub(crate) fn default_distortion_model(num_coeffs: usize) -> &'static str {
+    match num_coeffs {
+        8 => "rational_polynomial",
+        _ => "plumb_bob",
+    }
+}
+
+/// Copies a matrix into a fixed size array, failing if the sizes do not match
+pub(crate) fn into_array<const N: usize>(name: &str, values: &[f64]) -> Result<[f64; N], Box<dyn Error>> {
+    match values.try_into() {
+        Ok(array) => Ok(array),
+        Err(_) => Err(format!("Invalid number of elements in {} [expected: {}, found: {}]", name, N, values.len()))?
+    }
+}
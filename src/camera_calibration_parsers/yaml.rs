@@ -0,0 +1,166 @@
+//! yaml module reads and writes calibration files in the YAML format of the
+//! `camera_calibration_parsers` package, as written by the `camera_calibration`
+//! node.
+//!
+//! # Format
+//! ```text
+//! image_width: 640
+//! image_height: 480
+//! camera_name: narrow_stereo
+//! camera_matrix:
+//!   rows: 3
+//!   cols: 3
+//!   data: [430.15, 0, 311.71, 0, 430.53, 221.07, 0, 0, 1]
+//! distortion_model: plumb_bob
+//! distortion_coefficients:
+//!   rows: 1
+//!   cols: 5
+//!   data: [-0.337, 0.117, 0.001, -0.0002, 0]
+//! rectification_matrix:
+//!   rows: 3
+//!   cols: 3
+//!   data: [1, 0, 0, 0, 1, 0, 0, 0, 1]
+//! projection_matrix:
+//!   rows: 3
+//!   cols: 4
+//!   data: [357.43, 0, 306.91, 0, 0, 362.73, 222.09, 0, 0, 0, 1, 0]
+//! ```
+//!
+//! `camera_name` is optional and `distortion_model` defaults to `rational_polynomial`
+//! when there are 8 distortion coefficients and `plumb_bob` otherwise.
+
+use serde::Deserialize;
+use std::error::Error;
+
+use crate::msgs::sensor_msgs::CameraInfo;
+use super::{
+    default_distortion_model,
+    into_array,
+};
+
+#[derive(Deserialize)]
+struct CalibrationYaml {
+    image_width: u32,
+    image_height: u32,
+    #[serde(default)]
+    camera_name: String,
+    camera_matrix: MatrixYaml,
+    distortion_model: Option<String>,
+    distortion_coefficients: MatrixYaml,
+    rectification_matrix: MatrixYaml,
+    projection_matrix: MatrixYaml,
+}
+
+#[derive(Deserialize)]
+struct MatrixYaml {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+impl MatrixYaml {
+    /// Checks the matrix dimensions and returns its data
+    fn into_data(self, name: &str, rows: Option<usize>, cols: Option<usize>) -> Result<Vec<f64>, Box<dyn Error>> {
+        if rows.is_some_and(|rows| rows != self.rows) || cols.is_some_and(|cols| cols != self.cols) {
+            Err(format!(
+                "Invalid dimensions for {} [expected: {}x{}, found: {}x{}]",
+                name,
+                rows.map_or("n".to_string(), |rows| rows.to_string()),
+                cols.map_or("n".to_string(), |cols| cols.to_string()),
+                self.rows,
+                self.cols
+            ))?
+        }
+
+        if self.rows * self.cols != self.data.len() {
+            Err(format!(
+                "Invalid number of elements in {} [rows: {}, cols: {}, elements: {}]",
+                name, self.rows, self.cols, self.data.len()
+            ))?
+        }
+
+        Ok(self.data)
+    }
+}
+
+/// Parses a calibration in the YAML format.
+///
+/// ## Arguments
+/// * `buffer` - Content of the calibration file
+///
+/// ## Returns
+/// * `(camera_name, camera_info)` tuple or an error if the calibration is malformed
+pub fn parse_calibration(buffer: &str) -> Result<(String, CameraInfo), Box<dyn Error>> {
+    let calibration: CalibrationYaml = serde_yaml::from_str(buffer)?;
+
+    let k = calibration.camera_matrix.into_data("camera_matrix", Some(3), Some(3))?;
+    let d = calibration.distortion_coefficients.into_data("distortion_coefficients", Some(1), None)?;
+    let r = calibration.rectification_matrix.into_data("rectification_matrix", Some(3), Some(3))?;
+    let p = calibration.projection_matrix.into_data("projection_matrix", Some(3), Some(4))?;
+
+    let distortion_model = calibration.distortion_model
+        .unwrap_or_else(|| default_distortion_model(d.len()).to_string());
+
+    let camera_info = CameraInfo {
+        width: calibration.image_width,
+        height: calibration.image_height,
+        distortion_model,
+        K: into_array("camera_matrix", &k)?,
+        R: into_array("rectification_matrix", &r)?,
+        P: into_array("projection_matrix", &p)?,
+        D: d,
+        ..Default::default()
+    };
+
+    Ok((calibration.camera_name, camera_info))
+}
+
+/// Formats a calibration in the YAML format.
+///
+/// ## Arguments
+/// * `camera_name` - Name of the camera stored in the file
+/// * `camera_info` - `sensor_msgs::CameraInfo` message containing the calibration
+///
+/// ## Returns
+/// * Content of the calibration file
+pub fn format_calibration(camera_name: &str, camera_info: &CameraInfo) -> String {
+    // Quote the name so that names like "yes" or "1" stay strings
+    let camera_name = serde_yaml::to_string(camera_name)
+        .map(|name| name.trim_end().to_string())
+        .unwrap_or_default();
+
+    format!(
+        "image_width: {}\n\
+         image_height: {}\n\
+         camera_name: {}\n\
+         camera_matrix:\n{}\
+         distortion_model: {}\n\
+         distortion_coefficients:\n{}\
+         rectification_matrix:\n{}\
+         projection_matrix:\n{}",
+        camera_info.width,
+        camera_info.height,
+        camera_name,
+        format_matrix(&camera_info.K, 3, 3),
+        camera_info.distortion_model,
+        format_matrix(&camera_info.D, 1, camera_info.D.len()),
+        format_matrix(&camera_info.R, 3, 3),
+        format_matrix(&camera_info.P, 3, 4),
+    )
+}
+
+fn format_matrix(data: &[f64], rows: usize, cols: usize) -> String {
+    let data: Vec<String> = data.iter().map(|value| format_value(*value)).collect();
+
+    format!("  rows: {}\n  cols: {}\n  data: [{}]\n", rows, cols, data.join(", "))
+}
+
+/// Formats a value so that serde_yaml parses it back to the same `f64`
+fn format_value(value: f64) -> String {
+    match value {
+        value if value.is_nan() => ".nan".to_string(),
+        value if value.is_infinite() && value > 0.0 => ".inf".to_string(),
+        value if value.is_infinite() => "-.inf".to_string(),
+        value => format!("{:?}", value),
+    }
+}
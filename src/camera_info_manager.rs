@@ -0,0 +1,315 @@
+//! camera_info_manager module contains the `CameraInfoManager` struct which
+//! provides the calibration of a camera driver, like the `camera_info_manager`
+//! ROS package. It loads the calibration from a URL, handles the
+//! `sensor_msgs/SetCameraInfo` service by storing the new calibration to disk
+//! and stamps the `sensor_msgs::CameraInfo` messages published with each image.
+//!
+//! ## Supported URLs
+//! * `file:///path/to/calibration.yaml` - Absolute path of a calibration file
+//! * `package://package_name/path/to/calibration.yaml` - Path relative to a package
+//!   found in `ROS_PACKAGE_PATH`
+//! * `""` - Default URL `file://${ROS_HOME}/camera_info/${NAME}.yaml`, or
+//!   `file://${ROS_HOME}/camera_info/${NAME}.ini` without the `yaml` feature
+//!
+//! The URLs can contain the `${NAME}` (camera name) and `${ROS_HOME}` (`ROS_HOME`
+//! environment variable, defaulting to `~/.ros`) variables. The file format is
+//! selected by the extension, see the `camera_calibration_parsers` module.
+
+use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{
+    Path,
+    PathBuf,
+};
+
+use crate::camera_calibration_parsers;
+use crate::cv_image::CvImage;
+use crate::msgs::{
+    std_msgs::Header,
+    sensor_msgs::{
+        CameraInfo,
+        SetCameraInfoReq,
+        SetCameraInfoRes,
+    },
+};
+
+/// URL used when an empty URL is given
+#[cfg(feature = "yaml")]
+pub const DEFAULT_CAMERA_INFO_URL: &str = "file://${ROS_HOME}/camera_info/${NAME}.yaml";
+
+/// URL used when an empty URL is given. YAML files need the `yaml` feature,
+/// so the default calibration is an INI file without it.
+#[cfg(not(feature = "yaml"))]
+pub const DEFAULT_CAMERA_INFO_URL: &str = "file://${ROS_HOME}/camera_info/${NAME}.ini";
+
+pub struct CameraInfoManager {
+    camera_name: String,
+    url: String,
+    camera_info: CameraInfo,
+}
+
+impl CameraInfoManager {
+    /// Constructs a new `CameraInfoManager` and loads the calibration from the URL.
+    /// If the calibration file does not exist, the camera is uncalibrated.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let manager = CameraInfoManager::new("narrow_stereo", "package://my_camera/calibration/${NAME}.yaml").unwrap();
+    /// let camera_info = manager.camera_info_for_image(&cv_image);
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `camera_name` - Name of the camera. Only alphanumeric characters and `_` are allowed.
+    /// * `url` - URL of the calibration file
+    ///
+    /// ## Returns
+    /// * `CameraInfoManager` object or an error if the camera name, the URL or
+    ///   the calibration file is invalid
+    pub fn new(camera_name: &str, url: &str) -> Result<CameraInfoManager, Box<dyn Error>> {
+        let mut manager = CameraInfoManager {
+            camera_name: String::new(),
+            url: String::new(),
+            camera_info: CameraInfo::default(),
+        };
+
+        manager.set_camera_name(camera_name)?;
+        manager.load_camera_info(url)?;
+
+        Ok(manager)
+    }
+
+    /// Returns the name of the camera.
+    pub fn camera_name(&self) -> &str {
+        &self.camera_name
+    }
+
+    /// Sets the name of the camera, which is used to resolve `${NAME}` and is
+    /// stored in the calibration file. The calibration is not reloaded.
+    ///
+    /// ## Arguments
+    /// * `camera_name` - Name of the camera. Only alphanumeric characters and `_` are allowed.
+    pub fn set_camera_name(&mut self, camera_name: &str) -> Result<(), Box<dyn Error>> {
+        if camera_name.is_empty() || !camera_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            Err(format!("Invalid camera name [camera_name: {}]", camera_name))?
+        }
+
+        self.camera_name = camera_name.to_string();
+
+        Ok(())
+    }
+
+    /// Returns the URL of the calibration file.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the current calibration.
+    pub fn camera_info(&self) -> &CameraInfo {
+        &self.camera_info
+    }
+
+    /// Returns true if the camera has a calibration, that is, its intrinsic matrix is set.
+    pub fn is_calibrated(&self) -> bool {
+        self.camera_info.K[0] != 0.0
+    }
+
+    /// Returns true if the URL is supported.
+    ///
+    /// ## Arguments
+    /// * `url` - URL of the calibration file
+    pub fn validate_url(&self, url: &str) -> bool {
+        self.resolve_url(url).is_ok()
+    }
+
+    /// Loads the calibration from a new URL. If the calibration file does not
+    /// exist, the camera becomes uncalibrated.
+    ///
+    /// ## Arguments
+    /// * `url` - URL of the calibration file
+    pub fn load_camera_info(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
+        let path = self.resolve_url(url)?;
+
+        let camera_info = match path.exists() {
+            true => camera_calibration_parsers::read_calibration(&path)?.1,
+            false => CameraInfo::default(),
+        };
+
+        self.url = url.to_string();
+        self.camera_info = camera_info;
+
+        Ok(())
+    }
+
+    /// Sets the calibration and stores it in the calibration file. The
+    /// calibration is used even if it could not be stored, like in `camera_info_manager`.
+    ///
+    /// ## Arguments
+    /// * `camera_info` - New calibration
+    ///
+    /// ## Returns
+    /// * An error if the calibration file could not be written
+    pub fn set_camera_info(&mut self, camera_info: CameraInfo) -> Result<(), Box<dyn Error>> {
+        self.camera_info = camera_info;
+
+        let path = self.resolve_url(&self.url)?;
+        camera_calibration_parsers::write_calibration(&path, &self.camera_name, &self.camera_info)
+            .map_err(|e| format!("Failed to write calibration [path: {}, error: {}]", path.display(), e))?;
+
+        Ok(())
+    }
+
+    /// Handles a `sensor_msgs/SetCameraInfo` service request.
+    ///
+    /// ## Arguments
+    /// * `request` - Service request containing the new calibration
+    ///
+    /// ## Returns
+    /// * Service response with `success` set if the calibration was stored
+    pub fn handle_set_camera_info(&mut self, request: SetCameraInfoReq) -> SetCameraInfoRes {
+        match self.set_camera_info(request.camera_info) {
+            Ok(()) => SetCameraInfoRes {
+                success: true,
+                status_message: String::new(),
+            },
+            Err(e) => SetCameraInfoRes {
+                success: false,
+                status_message: e.to_string(),
+            },
+        }
+    }
+
+    /// Returns the calibration with the given header, to be published alongside an image.
+    ///
+    /// ## Arguments
+    /// * `header` - Header of the published image
+    pub fn stamped_camera_info(&self, header: &Header) -> CameraInfo {
+        let mut camera_info = self.camera_info.clone();
+        camera_info.header = header.clone();
+
+        camera_info
+    }
+
+    /// Returns the calibration with the header of the image, to be published alongside it.
+    ///
+    /// ## Arguments
+    /// * `image` - Published image
+    pub fn camera_info_for_image(&self, image: &CvImage) -> CameraInfo {
+        self.stamped_camera_info(image.header())
+    }
+
+    /// Advertises the `sensor_msgs/SetCameraInfo` service for a shared manager.
+    /// The service is stopped when the returned object is dropped.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let manager = Arc::new(Mutex::new(CameraInfoManager::new("camera", "").unwrap()));
+    /// let _service = CameraInfoManager::advertise_set_camera_info(&manager, "~set_camera_info").unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `manager` - Manager shared with the publishing code
+    /// * `service` - Name of the service, usually `set_camera_info` in the camera namespace
+    #[cfg(feature = "rosrust")]
+    pub fn advertise_set_camera_info(
+        manager: &std::sync::Arc<std::sync::Mutex<CameraInfoManager>>,
+        service: &str
+    ) -> Result<rosrust::Service, Box<dyn Error>> {
+        let manager = std::sync::Arc::clone(manager);
+
+        let service = rosrust::service::<crate::msgs::sensor_msgs::SetCameraInfo, _>(service, move |request| {
+            let mut manager = manager.lock().map_err(|e| e.to_string())?;
+            Ok(manager.handle_set_camera_info(request))
+        })?;
+
+        Ok(service)
+    }
+
+    /// Resolves the URL into a file path, using the `ROS_HOME` and
+    /// `ROS_PACKAGE_PATH` environment variables
+    fn resolve_url(&self, url: &str) -> Result<PathBuf, Box<dyn Error>> {
+        let ros_home = match env::var("ROS_HOME") {
+            Ok(ros_home) => ros_home,
+            Err(_) => match env::var("HOME") {
+                Ok(home) => format!("{}/.ros", home),
+                Err(_) => Err("Failed to resolve ${ROS_HOME}, neither ROS_HOME nor HOME is set".to_string())?
+            },
+        };
+        let package_path = env::var_os("ROS_PACKAGE_PATH").unwrap_or_default();
+
+        resolve_calibration_url(url, &self.camera_name, &ros_home, &package_path)
+    }
+}
+
+/// Resolves the variables and the scheme of a calibration URL into a file path.
+/// `CameraInfoManager` calls it with the values of the `ROS_HOME` and
+/// `ROS_PACKAGE_PATH` environment variables.
+///
+/// ## Arguments
+/// * `url` - URL of the calibration file, `""` for `DEFAULT_CAMERA_INFO_URL`
+/// * `camera_name` - Value of the `${NAME}` variable
+/// * `ros_home` - Value of the `${ROS_HOME}` variable
+/// * `package_path` - Package search path of `package://` URLs, in the format of `ROS_PACKAGE_PATH`
+///
+/// ## Returns
+/// * Path of the calibration file or an error if the URL is invalid or the package is not found
+pub fn resolve_calibration_url(url: &str, camera_name: &str, ros_home: &str, package_path: &OsStr) -> Result<PathBuf, Box<dyn Error>> {
+    let url = match url {
+        "" => DEFAULT_CAMERA_INFO_URL,
+        url => url,
+    };
+    let url = url
+        .replace("${NAME}", camera_name)
+        .replace("${ROS_HOME}", ros_home);
+
+    if let Some(path) = url.strip_prefix("file://") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Some(path) = url.strip_prefix("package://") {
+        let (package, path) = match path.split_once('/') {
+            Some((package, path)) if !package.is_empty() => (package, path),
+            _ => Err(format!("Invalid package URL [url: {}]", url))?
+        };
+
+        return Ok(find_package(package, package_path)?.join(path));
+    }
+
+    Err(format!("Unsupported camera calibration URL [url: {}]", url))?
+}
+
+/// Finds the directory of a package in the package search path
+fn find_package(package: &str, package_path: &OsStr) -> Result<PathBuf, Box<dyn Error>> {
+    for root in env::split_paths(package_path) {
+        if let Some(path) = search_package(&root, package) {
+            return Ok(path);
+        }
+    }
+
+    Err(format!("Package not found in package path [package: {}]", package))?
+}
+
+/// Recursively searches for a directory named `package` containing a `package.xml`.
+/// Like `rospack`, the search does not descend into packages.
+fn search_package(dir: &Path, package: &str) -> Option<PathBuf> {
+    if dir.join("package.xml").is_file() {
+        return match dir.file_name().is_some_and(|name| name == package) {
+            true => Some(dir.to_path_buf()),
+            false => None,
+        };
+    }
+
+    let entries = fs::read_dir(dir).ok()?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && path.is_dir() {
+            if let Some(path) = search_package(&path, package) {
+                return Some(path);
+            }
+        }
+    }
+
+    None
+}
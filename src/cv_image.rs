@@ -1,20 +1,24 @@
 //! cv_image module contains CvImage struct and its methods.
 //! CvImage wraps the image array and its metadata and acts as
 //! a bridge between the `sensor_msgs::Image` message and `cv::Mat`
+//! 
+//! The conversions to and from `cv::Mat` are only available with the
+//! `opencv` feature. Without it, `CvImage` can still be used to validate
+//! and fix the byte order of `sensor_msgs::Image` messages.
 
+#[cfg(feature = "opencv")]
 use opencv::prelude::*;
 use std::error::Error;
 
-use crate::msgs::{
-    std_msgs::Header,
-    sensor_msgs::Image,
-};
+use crate::msgs::std_msgs::Header;
+use crate::image_message::ImageMessage;
 use crate::utils::{
     image_encodings,
     image_byteorder_ops,
+    image_validation,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DataContainer {
     VecU8(Vec<u8>),
     VecI8(Vec<i8>),
@@ -25,7 +29,7 @@ pub enum DataContainer {
     VecF64(Vec<f64>),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CvImage {
     header: Header,
     height: usize,
@@ -35,46 +39,112 @@ pub struct CvImage {
 }
 
 impl CvImage {
-    /// Constructs a new `CvImage` from a `sensor_msgs::Image` message.
+    /// Constructs a new `CvImage` from its individual parts. The data is expected
+    /// to be tightly packed (no row padding) and in little-endian byte order.
     /// 
     /// # Example
     /// ```
-    /// let image = rosrust_msg::sensor_msgs::Image::default();
+    /// use cv_bridge::{
+    ///     CvImage,
+    ///     cv_image::DataContainer,
+    ///     msgs::std_msgs::Header,
+    /// };
+    /// 
+    /// let data = DataContainer::VecU8(vec![0; 640 * 480 * 3]);
+    /// let cv_image = CvImage::from_parts(Header::default(), 480, 640, "bgr8", data);
+    /// ```
+    /// 
+    /// ## Arguments
+    /// * `header` - `std_msgs::Header` message
+    /// * `height` - Height of the image
+    /// * `width` - Width of the image
+    /// * `encoding` - Encoding of the image. Check the supported encodings in the
+    ///   `image_encodings` module.
+    /// * `data` - `DataContainer` holding the image data
+    /// 
+    /// ## Returns
+    /// * `CvImage` object
+    pub fn from_parts(header: Header, height: usize, width: usize, encoding: &str, data: DataContainer) -> CvImage {
+        CvImage {
+            header,
+            height,
+            width,
+            encoding: encoding.to_string(),
+            data,
+        }
+    }
+
+    /// Consumes the `CvImage` and returns its individual parts.
+    /// 
+    /// ## Returns
+    /// * `(header, height, width, encoding, data)` tuple
+    pub fn into_parts(self) -> (Header, usize, usize, String, DataContainer) {
+        (self.header, self.height, self.width, self.encoding, self.data)
+    }
+
+    /// Constructs a new `CvImage` from a `sensor_msgs::Image` message or any
+    /// other message implementing `ImageMessage`. The message is checked with
+    /// `image_validation::validate_imgmsg` first, and the padding at the end of
+    /// its rows is removed.
+    /// 
+    /// # Example
+    /// ```no_run
+    /// # use cv_bridge::{CvImage, msgs::sensor_msgs::Image};
+    /// let image = Image::default();
     /// // set the image data
     /// let cv_image = CvImage::from_imgmsg(image).unwrap();
     /// ```
     /// 
     /// ## Arguments
-    /// * `image` - `rosrust_msg::sensor_msgs::Image` message 
+    /// * `image` - `sensor_msgs::Image` message implementing `ImageMessage`
     /// 
     /// ## Returns
     /// * `CvImage` object
-    pub fn from_imgmsg(image: Image) -> Result<CvImage, Box<dyn Error>> {
-        let bit_depth = image_encodings::get_bit_depth(image.encoding.as_str());
-       
+    pub fn from_imgmsg<T: ImageMessage>(image: T) -> Result<CvImage, Box<dyn Error>> {
+        image_validation::validate_imgmsg(&image)?;
+
+        let header = image.header()?;
+        let (height, width) = (image.height(), image.width());
+        let encoding = image.encoding().to_string();
+        let is_bigendian = image.is_bigendian();
+        let bit_depth = image_encodings::get_bit_depth(&encoding);
+
+        // Remove the padding at the end of the rows
+        let step = image.step() as usize;
+        let row_len = width as usize * image_validation::bytes_per_pixel(&encoding)?;
+        let data = match step == row_len {
+            true => image.into_data(),
+            false => image.data()
+                .chunks_exact(step)
+                .flat_map(|row| &row[..row_len])
+                .copied()
+                .collect(),
+        };
+
         let data = match bit_depth {
-            8 => DataContainer::VecU8(image.data),
-            16 => DataContainer::VecU8(match image.is_bigendian {
-                0 => image.data,
-                1 => image_byteorder_ops::from_be_to_le(&image.data),
-                _ => Err(format!("Unsupported endianness [endianness: {}]", image.is_bigendian))?
+            8 => DataContainer::VecU8(data),
+            16 => DataContainer::VecU8(match is_bigendian {
+                1 => image_byteorder_ops::from_be_to_le(&data),
+                _ => data,
             }),
+            32 => DataContainer::VecF32(image_byteorder_ops::from_u8_to_f32(&data, is_bigendian == 1)),
             _ => Err(format!("Unsupported bit depth for container [bit depth: {}]", bit_depth))?
         };
 
         Ok(CvImage {
-            header: image.header,
-            height: image.height as usize,
-            width: image.width as usize,
-            encoding: image.encoding,
-            data: data
+            header,
+            height: height as usize,
+            width: width as usize,
+            encoding,
+            data
         })
     }
 
     /// Constructs a new `CvImage` from a `cv::Mat` object.
     /// 
     /// # Example
-    /// ```
+    /// ```no_run
+    /// # use cv_bridge::CvImage;
     /// let mat = opencv::core::Mat::default();
     /// // set the image data
     /// let cv_image = CvImage::from_cvmat(mat, "bgr8").unwrap();
@@ -83,23 +153,25 @@ impl CvImage {
     /// ## Arguments
     /// * `mat` - `opencv::core::Mat` object
     /// * `encoding` - Encoding of the image. Note that `Mat` does not
-    ///                 contain any metadata about the image encoding, so it must be
-    ///                 tracked by the user.
+    ///   contain any metadata about the image encoding, so it must be
+    ///   tracked by the user.
     /// 
     /// ## Returns
     /// * `CvImage` object
+    #[cfg(feature = "opencv")]
     pub fn from_cvmat(mat: Mat, encoding: &str) -> Result<CvImage, Box<dyn Error>> {
         let (width, height) = (mat.cols(), mat.rows());
         let bit_depth = image_encodings::get_bit_depth(encoding);
 
         let data = match mat.data_bytes() {
             Ok(data) => data,
-            Err(_) => Err(format!("Could not get data from Mat"))?
+            Err(_) => Err("Could not get data from Mat".to_string())?
         };
         
         let data = match bit_depth {
             8 => DataContainer::VecU8(data.to_vec()),
             16 => DataContainer::VecU8(data.to_vec()),
+            32 => DataContainer::VecF32(image_byteorder_ops::from_u8_to_f32(data, false)),
             _ => Err(format!("Unsupported bit depth for container [bit depth: {}]", bit_depth))?
         };
 
@@ -108,67 +180,92 @@ impl CvImage {
             height: height as usize,
             width: width as usize,
             encoding: encoding.to_string(),
-            data: data,
+            data,
         })
     }
 
-    /// Converts the `CvImage` to a `sensor_msgs::Image` message.
+    /// Converts the `CvImage` to a `sensor_msgs::Image` message or any other
+    /// message implementing `ImageMessage`.
     /// 
     /// # Example:
-    /// ```
-    /// let mut cv_image = CvImage::from_imgmsg(image_msg).unwrap();
-    /// let image_msg = cv_image.to_imgmsg(0).unwrap();
+    /// ```no_run
+    /// # use cv_bridge::{CvImage, msgs::sensor_msgs::Image};
+    /// # let image_msg = Image::default();
+    /// let cv_image = CvImage::from_imgmsg(image_msg).unwrap();
+    /// let image_msg: Image = cv_image.into_imgmsg(0).unwrap();
     /// ```
     /// 
     /// ## Arguments
     /// * `is_bigendian` - Endianness of the image data. 0 for little-endian, 1 for big-endian.
     /// 
     /// ## Returns
-    /// * `sensor_msgs::Image` message
-    pub fn into_imgmsg(self, is_bigendian: u8) -> Result<Image, Box<dyn Error>> {
-        let step = self.width as u32 * image_encodings::get_num_channels(&self.encoding) as u32;
+    /// * `sensor_msgs::Image` message implementing `ImageMessage`
+    pub fn into_imgmsg<T: ImageMessage>(self, is_bigendian: u8) -> Result<T, Box<dyn Error>> {
+        let bytes_per_channel = image_encodings::get_bit_depth(&self.encoding) as u32 / 8;
+        let step = self.width as u32 * image_encodings::get_num_channels(&self.encoding) as u32 * bytes_per_channel;
         
+        if is_bigendian > 1 {
+            Err(format!("Unsupported endianness [is_bigendian: {}]", is_bigendian))?
+        }
+
+        // 16-bit data is stored in little-endian byte order
         let data = match self.data {
-            DataContainer::VecU8(data) => data,
+            DataContainer::VecU8(data) => match (bytes_per_channel, is_bigendian) {
+                (2, 1) => image_byteorder_ops::from_le_to_be(&data),
+                _ => data,
+            },
             DataContainer::VecU16(data) => image_byteorder_ops::from_u16_to_u8(&data, is_bigendian == 1),
-            _ => Err(format!("Unsupported container type"))?
+            DataContainer::VecF32(data) => image_byteorder_ops::from_f32_to_u8(&data, is_bigendian == 1),
+            _ => Err("Unsupported container type".to_string())?
         };
 
-        Ok(Image {
-            header: self.header,
-            height: self.height as u32,
-            width: self.width as u32,
-            encoding: self.encoding,
-            is_bigendian: is_bigendian,
-            step: step,
-            data: data
-        })
+        T::from_fields(
+            self.header,
+            self.height as u32,
+            self.width as u32,
+            self.encoding,
+            is_bigendian,
+            step,
+            data,
+        )
     }
 
     /// Converts the `CvImage` to a `CvImage` with a different encoding. It will copy the data
-    /// into the new buffer.
+    /// into the new buffer. The new image keeps the header of the original one.
     /// 
     /// # Example:
-    /// ```
+    /// ```no_run
+    /// # use cv_bridge::{CvImage, msgs::sensor_msgs::Image};
+    /// # let image_msg = Image::default();
     /// let mut cv_image = CvImage::from_imgmsg(image_msg).unwrap();
     /// let cv_image = cv_image.to_cvimage("mono8").unwrap();
     /// ```
     /// 
     /// ## Arguments
     /// * `desired_encoding` - Encoding of the new image. Check the supported encodings in the
-    ///                       `image_encodings` module.
+    ///   `image_encodings` module.
     /// 
     /// ## Returns
     /// * `CvImage` object
+    #[cfg(feature = "opencv")]
     pub fn to_cvimage(&mut self, desired_encoding: &str) -> Result<CvImage, Box<dyn Error>> {
         let src_enc = image_encodings::from_encstr_to_cvenc(&self.encoding)?;
         let dst_enc = image_encodings::from_encstr_to_cvenc(desired_encoding)?;
-        let convertion_code = image_encodings::get_conversion_code(src_enc, dst_enc)?;
 
         let src_mat = self.as_cvmat()?;
-        let mut dst_mat = Mat::default();
 
-        opencv::imgproc::cvt_color(&src_mat, &mut dst_mat, convertion_code, 0)?;
+        // Encodings with the same color space only differ by their bit depth
+        let dst_mat = match src_enc == dst_enc {
+            true => src_mat,
+            false => {
+                let convertion_code = image_encodings::get_conversion_code(src_enc, dst_enc)?;
+                let mut dst_mat = Mat::default();
+
+                opencv::imgproc::cvt_color(&src_mat, &mut dst_mat, convertion_code, 0)?;
+
+                dst_mat
+            },
+        };
 
         let cvtype = image_encodings::from_encstr_to_cvtype(desired_encoding)?;
         let scaling = image_encodings::get_scaling_factor(&self.encoding, desired_encoding);
@@ -177,7 +274,10 @@ impl CvImage {
         
         dst_mat.convert_to(&mut dst2_mat, cvtype, scaling, 0.0)?;
 
-        Ok(CvImage::from_cvmat(dst2_mat, desired_encoding)?)
+        let mut dst_image = CvImage::from_cvmat(dst2_mat, desired_encoding)?;
+        dst_image.header = self.header.clone();
+
+        Ok(dst_image)
     }
 
     /// Returns the image as a `cv::Mat` object. This is a cheap operation 
@@ -185,14 +285,24 @@ impl CvImage {
     /// 
     /// ## Returns
     /// * `opencv::core::Mat` object
+    #[cfg(feature = "opencv")]
     pub fn as_cvmat(&mut self) -> Result<Mat, Box<dyn Error>> {
         let cvtype = image_encodings::from_encstr_to_cvtype(&self.encoding)?;
-        
-        let buffer_mut_ptr = match self.data {
-            DataContainer::VecU8(ref mut data) => data.as_mut_ptr() as *mut _,
-            DataContainer::VecU16(ref mut data) => data.as_mut_ptr() as *mut _,
-            _ => Err(format!("Unsupported container type"))?
+        let expected_len = self.height * self.width * image_validation::bytes_per_pixel(&self.encoding)?;
+
+        // The Mat reads expected_len bytes through the raw pointer
+        let (buffer_mut_ptr, buffer_len) = match self.data {
+            DataContainer::VecU8(ref mut data) => (data.as_mut_ptr() as *mut _, data.len()),
+            DataContainer::VecU16(ref mut data) => (data.as_mut_ptr() as *mut _, data.len() * 2),
+            DataContainer::VecF32(ref mut data) => (data.as_mut_ptr() as *mut _, data.len() * 4),
+            _ => Err("Unsupported container type".to_string())?
         };
+        if buffer_len != expected_len {
+            Err(format!(
+                "Data length does not match the image size [data length: {}, expected: {}]",
+                buffer_len, expected_len
+            ))?
+        }
 
         let mat;
         unsafe {
@@ -0,0 +1,98 @@
+//! convert module converts depth images between integer millimeters (`16UC1`)
+//! and float meters (`32FC1`), like the `convert_metric` nodelet of
+//! `depth_image_proc`.
+//!
+//! Unlike `CvImage::to_cvimage`, which rescales values by the ratio of the bit
+//! depths, these conversions apply a metric scale and map invalid depths: 0 in
+//! `16UC1` images and NaN in `32FC1` images. Like the rest of `depth_image_proc`,
+//! only the `16UC1` and `32FC1` depth encodings are accepted.
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+
+/// Scale of `16UC1` depth images in meters per unit, that is, millimeters
+pub const DEFAULT_DEPTH_SCALE: f64 = 0.001;
+
+/// Converts a `16UC1` depth image into a `32FC1` depth image in meters. Pixels with a depth of 0 are set to NaN. The output keeps the header
+/// of the input.
+///
+/// # Example
+/// ```
+/// use cv_bridge::{
+///     CvImage,
+///     cv_image::DataContainer,
+///     depth_image_proc::convert::{DEFAULT_DEPTH_SCALE, convert_metric},
+///     msgs::std_msgs::Header,
+/// };
+///
+/// let data = [1500u16, 0].iter().flat_map(|mm| mm.to_le_bytes()).collect();
+/// let depth_mm = CvImage::from_parts(Header::default(), 1, 2, "16UC1", DataContainer::VecU8(data));
+/// let depth_m = convert_metric(&depth_mm, DEFAULT_DEPTH_SCALE).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `depth` - `16UC1` depth image
+/// * `scale` - Meters per unit of the input, `DEFAULT_DEPTH_SCALE` for millimeters
+///
+/// ## Returns
+/// * `32FC1` depth `CvImage` object or an error if the scale is not finite and positive
+pub fn convert_metric(depth: &CvImage, scale: f64) -> Result<CvImage, Box<dyn Error>> {
+    let data = match (depth.encoding().as_str(), depth.as_container()) {
+        ("16UC1", DataContainer::VecU8(data)) => data,
+        _ => Err(format!("Unsupported encoding for integer depth image [encoding: {}]", depth.encoding()))?
+    };
+
+    check_scale(scale)?;
+
+    let depth_m = data.chunks_exact(2)
+        .map(|bytes| match u16::from_le_bytes([bytes[0], bytes[1]]) {
+            0 => f32::NAN,
+            value => (value as f64 * scale) as f32,
+        })
+        .collect();
+
+    Ok(CvImage::from_parts(depth.header().clone(), depth.height(), depth.width(), "32FC1", DataContainer::VecF32(depth_m)))
+}
+
+/// Converts a `32FC1` depth image in meters into a `16UC1` depth image. Pixels
+/// with a non-finite or non-positive depth, or a depth which does not fit in 16
+/// bits, are set to 0. The output keeps the header of the input.
+///
+/// ## Arguments
+/// * `depth` - `32FC1` depth image in meters
+/// * `scale` - Meters per unit of the output, `DEFAULT_DEPTH_SCALE` for millimeters
+///
+/// ## Returns
+/// * `16UC1` depth `CvImage` object or an error if the scale is not finite and positive
+pub fn convert_raw(depth: &CvImage, scale: f64) -> Result<CvImage, Box<dyn Error>> {
+    let data = match (depth.encoding().as_str(), depth.as_container()) {
+        ("32FC1", DataContainer::VecF32(data)) => data,
+        _ => Err(format!("Unsupported encoding for float depth image [encoding: {}]", depth.encoding()))?
+    };
+
+    check_scale(scale)?;
+
+    let depth_raw = data.iter()
+        .map(|meters| (*meters as f64 / scale).round())
+        .map(|value| match value.is_finite() && value >= 1.0 && value <= u16::MAX as f64 {
+            true => value as u16,
+            false => 0,
+        })
+        .flat_map(|value| value.to_le_bytes())
+        .collect();
+
+    Ok(CvImage::from_parts(depth.header().clone(), depth.height(), depth.width(), "16UC1", DataContainer::VecU8(depth_raw)))
+}
+
+/// Checks that the depth scale is finite and positive
+fn check_scale(scale: f64) -> Result<(), Box<dyn Error>> {
+    if !scale.is_finite() || scale <= 0.0 {
+        Err(format!("Invalid depth scale [scale: {}]", scale))?
+    }
+
+    Ok(())
+}
@@ -0,0 +1,42 @@
+//! Processing of depth images, in the same way as the `depth_image_proc` ROS package.
+//!
+//! ## Supported depth encodings
+//! * `16UC1` - Depth in millimeters, 0 for invalid pixels
+//! * `32FC1` - Depth in meters, NaN for invalid pixels
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+
+pub mod convert;
+pub mod point_cloud;
+pub mod register;
+
+pub use convert::{
+    convert_metric,
+    convert_raw,
+};
+pub use point_cloud::point_cloud;
+pub use register::register;
+
+/// Returns the depth of each pixel in meters, NaN for invalid pixels.
+pub(crate) fn depth_in_meters(depth: &CvImage) -> Result<Vec<f32>, Box<dyn Error>> {
+    match (depth.encoding().as_str(), depth.as_container()) {
+        ("16UC1", DataContainer::VecU8(data)) => Ok(data.chunks_exact(2)
+            .map(|bytes| match u16::from_le_bytes([bytes[0], bytes[1]]) {
+                0 => f32::NAN,
+                value => (value as f64 * convert::DEFAULT_DEPTH_SCALE) as f32,
+            })
+            .collect()),
+        ("32FC1", DataContainer::VecF32(data)) => Ok(data.iter()
+            .map(|m| match m.is_finite() && *m > 0.0 {
+                true => *m,
+                false => f32::NAN,
+            })
+            .collect()),
+        _ => Err(format!("Unsupported encoding for depth image [encoding: {}]", depth.encoding()))?
+    }
+}
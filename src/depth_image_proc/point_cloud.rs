@@ -0,0 +1,139 @@
+//! point_cloud module converts depth images into `sensor_msgs::PointCloud2`
+//! messages, like the `point_cloud_xyz` and `point_cloud_xyzrgb` nodelets of
+//! `depth_image_proc`.
+//!
+//! The cloud is organized like the depth image, with one point per pixel in the
+//! frame of the depth image. Pixels with an invalid depth produce points with NaN
+//! coordinates, so the cloud is not dense.
+//!
+//! ## Point layout
+//! * XYZ - `x`, `y` and `z` `FLOAT32` fields at offsets 0, 4 and 8, 16 bytes per point
+//! * XYZRGB - as XYZ, plus an `rgb` `FLOAT32` field at offset 16 packing the color
+//!   as `0x00RRGGBB`, 32 bytes per point
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::image_geometry::PinholeCameraModel;
+use crate::image_proc::checked_pixel_len;
+use crate::msgs::sensor_msgs::{
+    CameraInfo,
+    PointCloud2,
+    PointField,
+};
+use super::depth_in_meters;
+
+/// Converts a depth image into a point cloud, colored if a color image registered
+/// to the depth image is given.
+///
+/// # Example
+/// ```ignore
+/// let cloud = point_cloud(&depth_image, &camera_info, Some(&color_image)).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `depth` - `16UC1` (millimeters) or `32FC1` (meters) depth image
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the depth image
+/// * `color` - Color image registered to the depth image, with the same size. The
+///   supported encodings are `rgb8`, `bgr8`, `rgba8`, `bgra8` and `mono8`.
+///
+/// ## Returns
+/// * `sensor_msgs::PointCloud2` message with the header of the depth image
+pub fn point_cloud(depth: &CvImage, camera_info: &CameraInfo, color: Option<&CvImage>) -> Result<PointCloud2, Box<dyn Error>> {
+    let model = PinholeCameraModel::from_camera_info(camera_info)?;
+    checked_pixel_len(depth)?;
+    let depth_m = depth_in_meters(depth)?;
+
+    let colors = match color {
+        Some(color) => {
+            if color.width() != depth.width() || color.height() != depth.height() {
+                Err(format!(
+                    "Color image size does not match depth image [color: {}x{}, depth: {}x{}]",
+                    color.width(), color.height(), depth.width(), depth.height()
+                ))?
+            }
+            checked_pixel_len(color)?;
+            Some(rgb_values(color)?)
+        },
+        None => None,
+    };
+
+    let point_step = match colors {
+        Some(_) => 32,
+        None => 16,
+    };
+    let mut fields = vec![
+        float32_field("x", 0),
+        float32_field("y", 4),
+        float32_field("z", 8),
+    ];
+    if colors.is_some() {
+        fields.push(float32_field("rgb", 16));
+    }
+
+    let (fx, fy, cx, cy) = (model.fx(), model.fy(), model.cx(), model.cy());
+    let mut data = vec![0u8; depth_m.len() * point_step];
+
+    for (i, (point, z)) in data.chunks_exact_mut(point_step).zip(&depth_m).enumerate() {
+        let (u, v) = ((i % depth.width()) as f64, (i / depth.width()) as f64);
+        let (x, y, z) = match z.is_nan() {
+            true => (f32::NAN, f32::NAN, f32::NAN),
+            false => {
+                let z = *z as f64;
+                (((u - cx) * z / fx) as f32, ((v - cy) * z / fy) as f32, z as f32)
+            },
+        };
+
+        point[0..4].copy_from_slice(&x.to_le_bytes());
+        point[4..8].copy_from_slice(&y.to_le_bytes());
+        point[8..12].copy_from_slice(&z.to_le_bytes());
+
+        if let Some(colors) = &colors {
+            let [r, g, b] = colors[i];
+            point[16..20].copy_from_slice(&[b, g, r, 0]);
+        }
+    }
+
+    Ok(PointCloud2 {
+        header: depth.header().clone(),
+        height: depth.height() as u32,
+        width: depth.width() as u32,
+        fields,
+        is_bigendian: false,
+        point_step: point_step as u32,
+        row_step: (point_step * depth.width()) as u32,
+        data,
+        is_dense: false,
+    })
+}
+
+fn float32_field(name: &str, offset: u32) -> PointField {
+    PointField {
+        name: name.to_string(),
+        offset,
+        datatype: PointField::FLOAT32,
+        count: 1,
+    }
+}
+
+/// Returns the `[r, g, b]` color of each pixel
+fn rgb_values(color: &CvImage) -> Result<Vec<[u8; 3]>, Box<dyn Error>> {
+    let data = match color.as_container() {
+        DataContainer::VecU8(data) => data,
+        _ => Err("Unsupported container type".to_string())?
+    };
+
+    let rgb = match color.encoding().as_str() {
+        "rgb8" => data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+        "bgr8" => data.chunks_exact(3).map(|p| [p[2], p[1], p[0]]).collect(),
+        "rgba8" => data.chunks_exact(4).map(|p| [p[0], p[1], p[2]]).collect(),
+        "bgra8" => data.chunks_exact(4).map(|p| [p[2], p[1], p[0]]).collect(),
+        "mono8" => data.iter().map(|p| [*p, *p, *p]).collect(),
+        encoding => Err(format!("Unsupported encoding for color image [encoding: {}]", encoding))?
+    };
+
+    Ok(rgb)
+}
@@ -0,0 +1,109 @@
+//! register module reprojects a depth image into the frame of a color camera,
+//! like the `register` nodelet of `depth_image_proc`, so that the registered depth
+//! is aligned pixel for pixel with the color image.
+//!
+//! Each valid depth pixel is back-projected to 3D with the depth camera model,
+//! transformed into the color camera frame and projected with the color camera
+//! model. When several depth pixels land on the same color pixel, the nearest one
+//! is kept (z-buffering), so surfaces occluded from the color camera do not show
+//! through. Both images are expected to be rectified.
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::image_geometry::PinholeCameraModel;
+use crate::msgs::sensor_msgs::CameraInfo;
+use super::{
+    convert::DEFAULT_DEPTH_SCALE,
+    depth_in_meters,
+};
+
+/// Registers a depth image into the frame of a color camera.
+///
+/// # Example
+/// ```ignore
+/// let (registered, registered_info) = register(&depth_image, &depth_info, &color_info, &depth_to_color).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `depth` - `16UC1` (millimeters) or `32FC1` (meters) depth image
+/// * `depth_info` - `sensor_msgs::CameraInfo` message of the depth camera
+/// * `color_info` - `sensor_msgs::CameraInfo` message of the color camera
+/// * `depth_to_color` - 4x4 row-major rigid transform from the depth camera optical
+///   frame to the color camera optical frame
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the registered depth image, with the encoding
+///   of the input and the size of the color image, and its camera info. Both
+///   headers have the stamp of the depth image and the frame id of the color camera.
+///   Pixels without depth are 0 for `16UC1` and NaN for `32FC1`.
+pub fn register(
+    depth: &CvImage,
+    depth_info: &CameraInfo,
+    color_info: &CameraInfo,
+    depth_to_color: &[f64; 16]
+) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    let depth_model = PinholeCameraModel::from_camera_info(depth_info)?;
+    let color_model = PinholeCameraModel::from_camera_info(color_info)?;
+    let depth_m = depth_in_meters(depth)?;
+
+    let (width, height) = color_model.reduced_resolution();
+    let (width, height) = (width as usize, height as usize);
+    let mut registered = vec![f32::NAN; width * height];
+
+    let t = depth_to_color;
+    for (i, z) in depth_m.iter().enumerate() {
+        if z.is_nan() {
+            continue;
+        }
+
+        let (u, v) = ((i % depth.width()) as f64, (i / depth.width()) as f64);
+        let z = *z as f64;
+        let x = (u - depth_model.cx()) * z / depth_model.fx();
+        let y = (v - depth_model.cy()) * z / depth_model.fy();
+
+        let color_x = t[0] * x + t[1] * y + t[2] * z + t[3];
+        let color_y = t[4] * x + t[5] * y + t[6] * z + t[7];
+        let color_z = t[8] * x + t[9] * y + t[10] * z + t[11];
+        if color_z <= 0.0 {
+            continue;
+        }
+
+        let color_u = (color_model.fx() * color_x + color_model.tx()) / color_z + color_model.cx();
+        let color_v = (color_model.fy() * color_y + color_model.ty()) / color_z + color_model.cy();
+        let (color_u, color_v) = ((color_u + 0.5).floor(), (color_v + 0.5).floor());
+        if color_u < 0.0 || color_v < 0.0 || color_u >= width as f64 || color_v >= height as f64 {
+            continue;
+        }
+
+        let pixel = &mut registered[color_v as usize * width + color_u as usize];
+        let color_z = color_z as f32;
+        if pixel.is_nan() || color_z < *pixel {
+            *pixel = color_z;
+        }
+    }
+
+    let data = match depth.encoding().as_str() {
+        "16UC1" => DataContainer::VecU8(registered.iter()
+            .map(|meters| (*meters as f64 / DEFAULT_DEPTH_SCALE).round())
+            // Depths out of the 16-bit range are invalid, as in `convert_raw`
+            .map(|value| match value.is_finite() && value >= 1.0 && value <= u16::MAX as f64 {
+                true => value as u16,
+                false => 0,
+            })
+            .flat_map(|value| value.to_le_bytes())
+            .collect()),
+        _ => DataContainer::VecF32(registered),
+    };
+
+    let mut header = depth.header().clone();
+    header.frame_id = color_info.header.frame_id.clone();
+
+    let mut registered_info = color_info.clone();
+    registered_info.header = header.clone();
+
+    Ok((CvImage::from_parts(header, height, width, depth.encoding(), data), registered_info))
+}
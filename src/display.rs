@@ -0,0 +1,187 @@
+//! display module converts `CvImage`s of any supported encoding into `bgr8`
+//! images which can be shown with `highgui::imshow` or published for viewing,
+//! like `cv_bridge::cvtColorForDisplay` in C++.
+//!
+//! Single channel images (`mono8`, `mono16`, `8UC1`, `16UC1`, `32FC1`), such as
+//! depth or disparity images, are scaled to 8 bits over a value range and
+//! optionally colorized with an OpenCV colormap. Invalid values (non-finite
+//! values, and 0 for `16UC1` depth images) are drawn with a fixed color.
+//!
+//! Generic multi-channel images with 3 or 4 channels (`8UC3`, `16UC4`, `32FC3`, ...)
+//! are scaled the same way channel by channel, with the channels taken as `bgr`
+//! or `bgra`; the alpha channel is dropped. Pixels with a non-finite channel are
+//! drawn with the invalid color. Color, Bayer and YUV images are converted to `bgr8`.
+//!
+//! Colormaps and the conversion of color, Bayer and YUV images need the `opencv`
+//! feature, the scaling of single channel and generic images does not.
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::utils::image_encodings;
+
+/// Range of values mapped to black and white (or the ends of the colormap)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DisplayRange {
+    /// Minimum and maximum of the valid values of each image
+    Auto,
+    /// Fixed range, for example `0.0..10.0` meters for depth images
+    Fixed { min: f64, max: f64 },
+    /// Full range of the encoding: 0 to 255 for 8 bits, 0 to 65535 for 16 bits
+    /// and 0 to 1 for floating point images
+    Encoding,
+}
+
+/// Options of `CvImage::to_display_image`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayOptions {
+    /// Range of values of single channel and generic multi-channel images
+    pub range: DisplayRange,
+    /// OpenCV colormap applied to single channel images (eg. `opencv::imgproc::COLORMAP_JET`),
+    /// or `None` for grayscale
+    pub colormap: Option<i32>,
+    /// `[b, g, r]` color of invalid values
+    pub invalid_color: [u8; 3],
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        DisplayOptions {
+            range: DisplayRange::Auto,
+            colormap: None,
+            invalid_color: [0, 0, 0],
+        }
+    }
+}
+
+impl CvImage {
+    /// Converts the image into a `bgr8` image for display. The output keeps the
+    /// header of the input.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let options = DisplayOptions {
+    ///     range: DisplayRange::Fixed { min: 0.0, max: 5.0 },
+    ///     colormap: Some(opencv::imgproc::COLORMAP_TURBO),
+    ///     ..Default::default()
+    /// };
+    /// let mut display_image = depth_image.to_display_image(&options).unwrap();
+    /// highgui::imshow("depth", &display_image.as_cvmat().unwrap()).unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `options` - Range, colormap and invalid color of single channel and
+    ///   generic multi-channel images
+    ///
+    /// ## Returns
+    /// * `bgr8` `CvImage` object
+    pub fn to_display_image(&mut self, options: &DisplayOptions) -> Result<CvImage, Box<dyn Error>> {
+        let encoding = self.encoding().clone();
+        if encoding == "bgr8" {
+            return Ok(self.clone());
+        }
+
+        let channels = image_encodings::get_num_channels(&encoding);
+        let generic = matches!(&encoding[..], "8UC3" | "8UC4" | "16UC3" | "16UC4" | "32FC3" | "32FC4");
+        let single = channels == 1 && !encoding.starts_with("bayer_");
+        if !generic && !single {
+            return self.color_to_bgr8();
+        }
+
+        let values = self.channel_values()?;
+        let is_valid = |value: &f64| value.is_finite() && !(encoding == "16UC1" && *value == 0.0);
+        // The alpha channel is not displayed, so it does not take part in the range
+        let displayed = values.chunks_exact(channels).flat_map(|pixel| pixel.iter().take(3));
+        let (min, max) = display_range(&options.range, &encoding, displayed.filter(|value| is_valid(value)));
+        let scale = match max > min {
+            true => 255.0 / (max - min),
+            false => 0.0,
+        };
+        let to_u8 = |value: &f64| match is_valid(value) {
+            true => ((value - min) * scale).round().clamp(0.0, 255.0) as u8,
+            false => 0,
+        };
+
+        // Validity of each pixel, false if any of its displayed channels is invalid
+        let valid: Vec<bool> = values.chunks_exact(channels)
+            .map(|pixel| pixel.iter().take(3).all(is_valid))
+            .collect();
+
+        let mut bgr: Vec<u8> = match (channels, options.colormap) {
+            (1, Some(colormap)) => {
+                let gray: Vec<u8> = values.iter().map(to_u8).collect();
+                apply_colormap(&gray, self.height(), self.width(), colormap)?
+            },
+            (1, None) => values.iter().flat_map(|value| [to_u8(value); 3]).collect(),
+            _ => values.chunks_exact(channels)
+                .flat_map(|pixel| [to_u8(&pixel[0]), to_u8(&pixel[1]), to_u8(&pixel[2])])
+                .collect(),
+        };
+
+        for (pixel, valid) in bgr.chunks_exact_mut(3).zip(&valid) {
+            if !valid {
+                pixel.copy_from_slice(&options.invalid_color);
+            }
+        }
+
+        Ok(CvImage::from_parts(self.header().clone(), self.height(), self.width(), "bgr8", DataContainer::VecU8(bgr)))
+    }
+
+    /// Returns the channel values of an 8-bit, 16-bit or `32FC*` image
+    fn channel_values(&self) -> Result<Vec<f64>, Box<dyn Error>> {
+        let values = match (image_encodings::get_bit_depth(self.encoding()), self.as_container()) {
+            (8, DataContainer::VecU8(data)) => data.iter().map(|v| *v as f64).collect(),
+            (16, DataContainer::VecU8(data)) => data.chunks_exact(2)
+                .map(|v| u16::from_le_bytes([v[0], v[1]]) as f64)
+                .collect(),
+            (32, DataContainer::VecF32(data)) => data.iter().map(|v| *v as f64).collect(),
+            _ => Err(format!("Unsupported encoding for display [encoding: {}]", self.encoding()))?
+        };
+
+        Ok(values)
+    }
+
+    #[cfg(feature = "opencv")]
+    fn color_to_bgr8(&mut self) -> Result<CvImage, Box<dyn Error>> {
+        self.to_cvimage("bgr8")
+    }
+
+    #[cfg(not(feature = "opencv"))]
+    fn color_to_bgr8(&mut self) -> Result<CvImage, Box<dyn Error>> {
+        Err(format!("Displaying this encoding requires the opencv feature [encoding: {}]", self.encoding()))?
+    }
+}
+
+/// Returns the `(min, max)` values mapped to 0 and 255
+fn display_range<'a>(range: &DisplayRange, encoding: &str, valid_values: impl Iterator<Item = &'a f64>) -> (f64, f64) {
+    match *range {
+        DisplayRange::Fixed { min, max } => (min, max),
+        DisplayRange::Encoding => match image_encodings::get_bit_depth(encoding) {
+            8 => (0.0, 255.0),
+            16 => (0.0, 65535.0),
+            _ => (0.0, 1.0),
+        },
+        DisplayRange::Auto => valid_values
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| (min.min(*value), max.max(*value))),
+    }
+}
+
+/// Applies an OpenCV colormap to a `mono8` buffer, returning `bgr8` pixels
+#[cfg(feature = "opencv")]
+fn apply_colormap(gray: &[u8], height: usize, width: usize, colormap: i32) -> Result<Vec<u8>, Box<dyn Error>> {
+    use opencv::prelude::*;
+
+    let gray_mat = Mat::from_slice_rows_cols(gray, height, width)?;
+    let mut color_mat = Mat::default();
+    opencv::imgproc::apply_color_map(&gray_mat, &mut color_mat, colormap)?;
+
+    Ok(color_mat.data_bytes()?.to_vec())
+}
+
+#[cfg(not(feature = "opencv"))]
+fn apply_colormap(_gray: &[u8], _height: usize, _width: usize, colormap: i32) -> Result<Vec<u8>, Box<dyn Error>> {
+    Err(format!("Colormaps require the opencv feature [colormap: {}]", colormap))?
+}
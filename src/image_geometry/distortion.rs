@@ -0,0 +1,152 @@
+//! Lens distortion models used by `sensor_msgs::CameraInfo`
+//! 
+//! ## Supported models
+//! * `plumb_bob` - 5 parameter polynomial model (k1, k2, p1, p2, k3)
+//! * `rational_polynomial` - 8 parameter rational model (k1, k2, p1, p2, k3, k4, k5, k6)
+//! * `equidistant` - 4 parameter fisheye model (k1, k2, k3, k4)
+//! 
+//! The functions in this module work on normalized image coordinates, that is,
+//! pixel coordinates with the intrinsic matrix removed.
+
+use std::error::Error;
+
+/// Number of iterations used when inverting the distortion
+const UNDISTORT_ITERATIONS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistortionModel {
+    PlumbBob,
+    RationalPolynomial,
+    Equidistant,
+}
+
+impl DistortionModel {
+    /// Returns the distortion model for the given `distortion_model` string
+    /// 
+    /// ## Arguments
+    /// * `model` - The distortion model string (e.g. "plumb_bob")
+    /// 
+    /// ## Returns
+    /// The distortion model or an error if the model is not supported
+    pub fn from_model_str(model: &str) -> Result<DistortionModel, Box<dyn Error>> {
+        match model {
+            "plumb_bob" => Ok(DistortionModel::PlumbBob),
+            "rational_polynomial" => Ok(DistortionModel::RationalPolynomial),
+            "equidistant" | "fisheye" => Ok(DistortionModel::Equidistant),
+            _ => Err(format!("Unsupported distortion model [model: {}]", model))?
+        }
+    }
+
+    /// Returns the `distortion_model` string used in `sensor_msgs::CameraInfo`
+    pub fn as_model_str(&self) -> &'static str {
+        match self {
+            DistortionModel::PlumbBob => "plumb_bob",
+            DistortionModel::RationalPolynomial => "rational_polynomial",
+            DistortionModel::Equidistant => "equidistant",
+        }
+    }
+}
+
+/// Returns the i-th distortion coefficient or 0 if it is not present
+fn coeff(d: &[f64], i: usize) -> f64 {
+    d.get(i).copied().unwrap_or(0.0)
+}
+
+/// Applies the lens distortion to a normalized image point
+/// 
+/// ## Arguments
+/// * `model` - The distortion model
+/// * `d` - The distortion coefficients
+/// * `point` - The undistorted normalized image point
+/// 
+/// ## Returns
+/// The distorted normalized image point
+pub fn distort(model: DistortionModel, d: &[f64], point: (f64, f64)) -> (f64, f64) {
+    let (x, y) = point;
+
+    match model {
+        DistortionModel::PlumbBob | DistortionModel::RationalPolynomial => {
+            let (k1, k2, p1, p2, k3) = (coeff(d, 0), coeff(d, 1), coeff(d, 2), coeff(d, 3), coeff(d, 4));
+            let (k4, k5, k6) = (coeff(d, 5), coeff(d, 6), coeff(d, 7));
+
+            let r2 = x * x + y * y;
+            let radial = (1.0 + ((k3 * r2 + k2) * r2 + k1) * r2) / (1.0 + ((k6 * r2 + k5) * r2 + k4) * r2);
+
+            (
+                x * radial + 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x),
+                y * radial + p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y,
+            )
+        },
+        DistortionModel::Equidistant => {
+            let (k1, k2, k3, k4) = (coeff(d, 0), coeff(d, 1), coeff(d, 2), coeff(d, 3));
+
+            let r = (x * x + y * y).sqrt();
+            if r == 0.0 {
+                return (x, y);
+            }
+
+            let theta = r.atan();
+            let theta2 = theta * theta;
+            let theta_d = theta * (1.0 + theta2 * (k1 + theta2 * (k2 + theta2 * (k3 + theta2 * k4))));
+            let scale = theta_d / r;
+
+            (x * scale, y * scale)
+        },
+    }
+}
+
+/// Removes the lens distortion from a normalized image point. The distortion is
+/// inverted iteratively in the same way as `cv::undistortPoints`.
+/// 
+/// ## Arguments
+/// * `model` - The distortion model
+/// * `d` - The distortion coefficients
+/// * `point` - The distorted normalized image point
+/// 
+/// ## Returns
+/// The undistorted normalized image point
+pub fn undistort(model: DistortionModel, d: &[f64], point: (f64, f64)) -> (f64, f64) {
+    let (x0, y0) = point;
+
+    match model {
+        DistortionModel::PlumbBob | DistortionModel::RationalPolynomial => {
+            let (k1, k2, p1, p2, k3) = (coeff(d, 0), coeff(d, 1), coeff(d, 2), coeff(d, 3), coeff(d, 4));
+            let (k4, k5, k6) = (coeff(d, 5), coeff(d, 6), coeff(d, 7));
+
+            let (mut x, mut y) = (x0, y0);
+            for _ in 0..UNDISTORT_ITERATIONS {
+                let r2 = x * x + y * y;
+                let icdist = (1.0 + ((k6 * r2 + k5) * r2 + k4) * r2) / (1.0 + ((k3 * r2 + k2) * r2 + k1) * r2);
+                let delta_x = 2.0 * p1 * x * y + p2 * (r2 + 2.0 * x * x);
+                let delta_y = p1 * (r2 + 2.0 * y * y) + 2.0 * p2 * x * y;
+
+                x = (x0 - delta_x) * icdist;
+                y = (y0 - delta_y) * icdist;
+            }
+
+            (x, y)
+        },
+        DistortionModel::Equidistant => {
+            let (k1, k2, k3, k4) = (coeff(d, 0), coeff(d, 1), coeff(d, 2), coeff(d, 3));
+
+            let theta_d = (x0 * x0 + y0 * y0).sqrt().min(std::f64::consts::FRAC_PI_2);
+            if theta_d == 0.0 {
+                return (x0, y0);
+            }
+
+            // Newton's method on theta * (1 + k1 theta^2 + ... + k4 theta^8) = theta_d
+            let mut theta = theta_d;
+            for _ in 0..UNDISTORT_ITERATIONS {
+                let theta2 = theta * theta;
+                let f = theta * (1.0 + theta2 * (k1 + theta2 * (k2 + theta2 * (k3 + theta2 * k4)))) - theta_d;
+                let df = 1.0 + theta2 * (3.0 * k1 + theta2 * (5.0 * k2 + theta2 * (7.0 * k3 + 9.0 * theta2 * k4)));
+
+                theta -= f / df;
+            }
+
+            let scale = theta.tan() / theta_d;
+
+            (x0 * scale, y0 * scale)
+        },
+    }
+}
@@ -0,0 +1,10 @@
+//! Camera models for interpreting images geometrically using the calibration
+//! parameters in `sensor_msgs::CameraInfo`, in the same way as the `image_geometry`
+//! ROS package.
+
+pub mod distortion;
+pub mod pinhole_camera_model;
+pub mod stereo_camera_model;
+
+pub use pinhole_camera_model::PinholeCameraModel;
+pub use stereo_camera_model::StereoCameraModel;
@@ -0,0 +1,331 @@
+//! pinhole_camera_model module contains the `PinholeCameraModel` struct which
+//! models a pinhole camera with an optional lens distortion.
+//! 
+//! The intrinsics returned by `fx`, `fy`, `cx`, `cy`, `tx` and `ty` as well as
+//! `intrinsic_matrix` and `projection_matrix` are adjusted for the binning and
+//! region of interest in the `CameraInfo`, so they apply to the images actually
+//! published by the camera. The full resolution matrices from the calibration are
+//! available through `full_intrinsic_matrix` and `full_projection_matrix`.
+
+use std::error::Error;
+
+use crate::msgs::sensor_msgs::{
+    CameraInfo,
+    RegionOfInterest,
+};
+use super::distortion::{
+    self,
+    DistortionModel,
+};
+
+#[derive(Debug, Clone)]
+pub struct PinholeCameraModel {
+    camera_info: CameraInfo,
+    distortion_model: Option<DistortionModel>,
+    k_full: [f64; 9],
+    p_full: [f64; 12],
+    k: [f64; 9],
+    p: [f64; 12],
+}
+
+impl PinholeCameraModel {
+    /// Constructs a new `PinholeCameraModel` from a `sensor_msgs::CameraInfo` message.
+    /// 
+    /// # Example
+    /// ```
+    /// use cv_bridge::{
+    ///     image_geometry::PinholeCameraModel,
+    ///     msgs::sensor_msgs::CameraInfo,
+    /// };
+    /// 
+    /// let mut info = CameraInfo::default();
+    /// info.width = 640;
+    /// info.height = 480;
+    /// info.K = [500.0, 0.0, 320.0, 0.0, 500.0, 240.0, 0.0, 0.0, 1.0];
+    /// info.R = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+    /// info.P = [500.0, 0.0, 320.0, 0.0, 0.0, 500.0, 240.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    /// 
+    /// let model = PinholeCameraModel::from_camera_info(&info).unwrap();
+    /// assert_eq!(model.project_3d_to_pixel((0.0, 0.0, 1.0)), (320.0, 240.0));
+    /// ```
+    /// 
+    /// ## Arguments
+    /// * `camera_info` - `sensor_msgs::CameraInfo` message
+    /// 
+    /// ## Returns
+    /// * `PinholeCameraModel` object or an error if the calibration is invalid
+    pub fn from_camera_info(camera_info: &CameraInfo) -> Result<PinholeCameraModel, Box<dyn Error>> {
+        if camera_info.K[0] == 0.0 || camera_info.K[4] == 0.0 {
+            Err(format!("Invalid intrinsic matrix [K: {:?}]", camera_info.K))?
+        }
+
+        if camera_info.P[0] == 0.0 || camera_info.P[5] == 0.0 {
+            Err(format!("Invalid projection matrix [P: {:?}]", camera_info.P))?
+        }
+
+        // A camera without distortion coefficients is treated as undistorted,
+        // regardless of the distortion model
+        let distortion_model = match camera_info.D.iter().all(|d| *d == 0.0) {
+            true => None,
+            false => Some(DistortionModel::from_model_str(&camera_info.distortion_model)?),
+        };
+
+        let k_full = camera_info.K;
+        let p_full = camera_info.P;
+        let mut k = k_full;
+        let mut p = p_full;
+
+        // The ROI is in full resolution coordinates, so it is applied before the binning
+        let roi = &camera_info.roi;
+        k[2] -= roi.x_offset as f64;
+        k[5] -= roi.y_offset as f64;
+        p[2] -= roi.x_offset as f64;
+        p[6] -= roi.y_offset as f64;
+
+        if camera_info.binning_x > 1 {
+            let scale_x = 1.0 / camera_info.binning_x as f64;
+            k[0] *= scale_x;
+            k[2] *= scale_x;
+            p[0] *= scale_x;
+            p[2] *= scale_x;
+            p[3] *= scale_x;
+        }
+
+        if camera_info.binning_y > 1 {
+            let scale_y = 1.0 / camera_info.binning_y as f64;
+            k[4] *= scale_y;
+            k[5] *= scale_y;
+            p[5] *= scale_y;
+            p[6] *= scale_y;
+            p[7] *= scale_y;
+        }
+
+        Ok(PinholeCameraModel {
+            camera_info: camera_info.clone(),
+            distortion_model,
+            k_full,
+            p_full,
+            k,
+            p,
+        })
+    }
+
+    /// Returns the immutable `sensor_msgs::CameraInfo` message the model was constructed from.
+    pub fn camera_info(&self) -> &CameraInfo {
+        &self.camera_info
+    }
+
+    /// Returns the frame id of the camera optical frame.
+    pub fn tf_frame(&self) -> &str {
+        &self.camera_info.header.frame_id
+    }
+
+    /// Returns the distortion model or `None` if the camera has no distortion.
+    pub fn distortion_model(&self) -> Option<DistortionModel> {
+        self.distortion_model
+    }
+
+    /// Returns the distortion coefficients.
+    pub fn distortion_coeffs(&self) -> &[f64] {
+        &self.camera_info.D
+    }
+
+    /// Returns the 3x3 row-major rectification matrix.
+    pub fn rotation_matrix(&self) -> &[f64; 9] {
+        &self.camera_info.R
+    }
+
+    /// Returns the 3x3 row-major intrinsic matrix adjusted for binning and ROI.
+    pub fn intrinsic_matrix(&self) -> &[f64; 9] {
+        &self.k
+    }
+
+    /// Returns the 3x4 row-major projection matrix adjusted for binning and ROI.
+    pub fn projection_matrix(&self) -> &[f64; 12] {
+        &self.p
+    }
+
+    /// Returns the 3x3 row-major intrinsic matrix at full resolution.
+    pub fn full_intrinsic_matrix(&self) -> &[f64; 9] {
+        &self.k_full
+    }
+
+    /// Returns the 3x4 row-major projection matrix at full resolution.
+    pub fn full_projection_matrix(&self) -> &[f64; 12] {
+        &self.p_full
+    }
+
+    /// Returns the focal length in x (in pixels).
+    pub fn fx(&self) -> f64 {
+        self.p[0]
+    }
+
+    /// Returns the focal length in y (in pixels).
+    pub fn fy(&self) -> f64 {
+        self.p[5]
+    }
+
+    /// Returns the x coordinate of the optical center (in pixels).
+    pub fn cx(&self) -> f64 {
+        self.p[2]
+    }
+
+    /// Returns the y coordinate of the optical center (in pixels).
+    pub fn cy(&self) -> f64 {
+        self.p[6]
+    }
+
+    /// Returns the x translation term of the projection matrix. For the right
+    /// camera of a stereo pair this is `-fx * baseline`.
+    pub fn tx(&self) -> f64 {
+        self.p[3]
+    }
+
+    /// Returns the y translation term of the projection matrix.
+    pub fn ty(&self) -> f64 {
+        self.p[7]
+    }
+
+    /// Returns the horizontal binning, 1 if the image is not binned.
+    pub fn binning_x(&self) -> u32 {
+        self.camera_info.binning_x.max(1)
+    }
+
+    /// Returns the vertical binning, 1 if the image is not binned.
+    pub fn binning_y(&self) -> u32 {
+        self.camera_info.binning_y.max(1)
+    }
+
+    /// Returns the region of interest in full resolution raw image coordinates.
+    /// If the `CameraInfo` has no ROI set, the full image is returned.
+    pub fn raw_roi(&self) -> RegionOfInterest {
+        let mut roi = self.camera_info.roi.clone();
+        if roi.width == 0 && roi.height == 0 {
+            roi.x_offset = 0;
+            roi.y_offset = 0;
+            roi.width = self.camera_info.width;
+            roi.height = self.camera_info.height;
+        }
+
+        roi
+    }
+
+    /// Returns the `(width, height)` of the full resolution image.
+    pub fn full_resolution(&self) -> (u32, u32) {
+        (self.camera_info.width, self.camera_info.height)
+    }
+
+    /// Returns the `(width, height)` of the published image after binning and ROI.
+    pub fn reduced_resolution(&self) -> (u32, u32) {
+        let roi = self.raw_roi();
+
+        (roi.width / self.binning_x(), roi.height / self.binning_y())
+    }
+
+    /// Converts a pixel in the published (binned and ROI) image to full resolution.
+    pub fn to_full_resolution(&self, uv_reduced: (f64, f64)) -> (f64, f64) {
+        let roi = &self.camera_info.roi;
+
+        (
+            uv_reduced.0 * self.binning_x() as f64 + roi.x_offset as f64,
+            uv_reduced.1 * self.binning_y() as f64 + roi.y_offset as f64,
+        )
+    }
+
+    /// Converts a full resolution pixel to the published (binned and ROI) image.
+    pub fn to_reduced_resolution(&self, uv_full: (f64, f64)) -> (f64, f64) {
+        let roi = &self.camera_info.roi;
+
+        (
+            (uv_full.0 - roi.x_offset as f64) / self.binning_x() as f64,
+            (uv_full.1 - roi.y_offset as f64) / self.binning_y() as f64,
+        )
+    }
+
+    /// Projects a 3D point in the camera optical frame onto the rectified image.
+    /// 
+    /// ## Arguments
+    /// * `xyz` - 3D point `(x, y, z)` in the camera optical frame. `z` must be non-zero.
+    /// 
+    /// ## Returns
+    /// * `(u, v)` pixel in the rectified image
+    pub fn project_3d_to_pixel(&self, xyz: (f64, f64, f64)) -> (f64, f64) {
+        let (x, y, z) = xyz;
+
+        (
+            (self.fx() * x + self.tx()) / z + self.cx(),
+            (self.fy() * y + self.ty()) / z + self.cy(),
+        )
+    }
+
+    /// Projects a pixel in the rectified image to a 3D ray in the camera optical frame.
+    /// 
+    /// ## Arguments
+    /// * `uv_rect` - `(u, v)` pixel in the rectified image
+    /// 
+    /// ## Returns
+    /// * `(x, y, z)` ray through the pixel, scaled so that `z = 1`
+    pub fn project_pixel_to_3d_ray(&self, uv_rect: (f64, f64)) -> (f64, f64, f64) {
+        let (u, v) = uv_rect;
+
+        (
+            (u - self.cx() - self.tx()) / self.fx(),
+            (v - self.cy() - self.ty()) / self.fy(),
+            1.0,
+        )
+    }
+
+    /// Converts a pixel in the raw (distorted) image to the rectified image.
+    /// 
+    /// ## Arguments
+    /// * `uv_raw` - `(u, v)` pixel in the raw image
+    /// 
+    /// ## Returns
+    /// * `(u, v)` pixel in the rectified image
+    pub fn rectify_point(&self, uv_raw: (f64, f64)) -> (f64, f64) {
+        let (k, p, r) = (&self.k, &self.p, &self.camera_info.R);
+
+        let normalized = ((uv_raw.0 - k[2]) / k[0], (uv_raw.1 - k[5]) / k[4]);
+        let (x, y) = match self.distortion_model {
+            Some(model) => distortion::undistort(model, &self.camera_info.D, normalized),
+            None => normalized,
+        };
+
+        // Rotate into the rectified frame and project with the left 3x3 part of P
+        let xr = r[0] * x + r[1] * y + r[2];
+        let yr = r[3] * x + r[4] * y + r[5];
+        let wr = r[6] * x + r[7] * y + r[8];
+
+        let w = p[8] * xr + p[9] * yr + p[10] * wr;
+
+        (
+            (p[0] * xr + p[1] * yr + p[2] * wr) / w,
+            (p[4] * xr + p[5] * yr + p[6] * wr) / w,
+        )
+    }
+
+    /// Converts a pixel in the rectified image to the raw (distorted) image.
+    /// 
+    /// ## Arguments
+    /// * `uv_rect` - `(u, v)` pixel in the rectified image
+    /// 
+    /// ## Returns
+    /// * `(u, v)` pixel in the raw image
+    pub fn unrectify_point(&self, uv_rect: (f64, f64)) -> (f64, f64) {
+        let (k, r) = (&self.k, &self.camera_info.R);
+        let (x, y, z) = self.project_pixel_to_3d_ray(uv_rect);
+
+        // Rotate the ray back into the raw camera frame with R^T
+        let xr = r[0] * x + r[3] * y + r[6] * z;
+        let yr = r[1] * x + r[4] * y + r[7] * z;
+        let zr = r[2] * x + r[5] * y + r[8] * z;
+
+        let normalized = (xr / zr, yr / zr);
+        let (xd, yd) = match self.distortion_model {
+            Some(model) => distortion::distort(model, &self.camera_info.D, normalized),
+            None => normalized,
+        };
+
+        (k[0] * xd + k[2], k[4] * yd + k[5])
+    }
+}
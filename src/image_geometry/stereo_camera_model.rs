@@ -0,0 +1,170 @@
+//! stereo_camera_model module contains the `StereoCameraModel` struct which
+//! models a calibrated stereo pair of pinhole cameras, with utilities to convert
+//! between disparity and depth.
+//!
+//! Disparities and depths follow the `stereo_msgs/DisparityImage` conventions:
+//! disparities are in pixels of the rectified left image and depths are in meters
+//! (or the unit of the baseline in the calibration).
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::msgs::sensor_msgs::CameraInfo;
+use super::PinholeCameraModel;
+
+#[derive(Debug, Clone)]
+pub struct StereoCameraModel {
+    left: PinholeCameraModel,
+    right: PinholeCameraModel,
+    q: [f64; 16],
+}
+
+impl StereoCameraModel {
+    /// Constructs a new `StereoCameraModel` from the `sensor_msgs::CameraInfo`
+    /// messages of the left and right cameras.
+    ///
+    /// ## Arguments
+    /// * `left` - `sensor_msgs::CameraInfo` message of the left camera
+    /// * `right` - `sensor_msgs::CameraInfo` message of the right camera. Its
+    ///   projection matrix must contain the translation `Tx = -fx * baseline`.
+    ///
+    /// ## Returns
+    /// * `StereoCameraModel` object or an error if the calibration is invalid
+    pub fn from_camera_info(left: &CameraInfo, right: &CameraInfo) -> Result<StereoCameraModel, Box<dyn Error>> {
+        let left = PinholeCameraModel::from_camera_info(left)?;
+        let right = PinholeCameraModel::from_camera_info(right)?;
+
+        if right.tx() == 0.0 {
+            Err("Right camera projection matrix has no baseline [Tx: 0]".to_string())?
+        }
+
+        // Reprojection matrix Q as computed by cv::stereoRectify, row-major 4x4
+        let tx = right.tx() / right.fx();
+        let mut q = [0.0; 16];
+        q[0] = left.fy() * tx;
+        q[3] = -left.fy() * left.cx() * tx;
+        q[5] = left.fx() * tx;
+        q[7] = -left.fx() * left.cy() * tx;
+        q[11] = left.fx() * left.fy() * tx;
+        q[14] = -left.fy();
+        q[15] = left.fy() * (left.cx() - right.cx());
+
+        Ok(StereoCameraModel {
+            left,
+            right,
+            q,
+        })
+    }
+
+    /// Returns the model of the left camera.
+    pub fn left(&self) -> &PinholeCameraModel {
+        &self.left
+    }
+
+    /// Returns the model of the right camera.
+    pub fn right(&self) -> &PinholeCameraModel {
+        &self.right
+    }
+
+    /// Returns the frame id of the stereo pair, which is the one of the left camera.
+    pub fn tf_frame(&self) -> &str {
+        self.left.tf_frame()
+    }
+
+    /// Returns the 4x4 row-major reprojection matrix `Q` which maps
+    /// `(u, v, disparity, 1)` to homogeneous 3D coordinates.
+    pub fn reprojection_matrix(&self) -> &[f64; 16] {
+        &self.q
+    }
+
+    /// Returns the distance between the optical centers of the cameras.
+    pub fn baseline(&self) -> f64 {
+        -self.right.tx() / self.right.fx()
+    }
+
+    /// Returns the depth at which a point has the given disparity.
+    ///
+    /// ## Arguments
+    /// * `disparity` - Disparity in pixels
+    ///
+    /// ## Returns
+    /// * Depth along the optical axis
+    pub fn get_z(&self, disparity: f64) -> f64 {
+        -self.right.tx() / (disparity - (self.left.cx() - self.right.cx()))
+    }
+
+    /// Returns the disparity observed for a point at the given depth.
+    ///
+    /// ## Arguments
+    /// * `z` - Depth along the optical axis
+    ///
+    /// ## Returns
+    /// * Disparity in pixels
+    pub fn get_disparity(&self, z: f64) -> f64 {
+        -self.right.tx() / z + (self.left.cx() - self.right.cx())
+    }
+
+    /// Projects a rectified pixel of the left image with a disparity to a 3D point
+    /// in the left camera optical frame.
+    ///
+    /// ## Arguments
+    /// * `left_uv_rect` - `(u, v)` pixel in the rectified left image
+    /// * `disparity` - Disparity of the pixel
+    ///
+    /// ## Returns
+    /// * `(x, y, z)` point in the left camera optical frame
+    pub fn project_disparity_to_3d(&self, left_uv_rect: (f64, f64), disparity: f64) -> (f64, f64, f64) {
+        let (u, v) = left_uv_rect;
+        let q = &self.q;
+
+        let x = q[0] * u + q[3];
+        let y = q[5] * v + q[7];
+        let z = q[11];
+        let w = q[14] * disparity + q[15];
+
+        (x / w, y / w, z / w)
+    }
+
+    /// Converts a `32FC1` disparity image into a `32FC1` depth image. Pixels whose
+    /// disparity is non-finite, or non-positive once corrected by the principal
+    /// point offset `cx_l - cx_r`, are set to NaN. The output keeps the header of
+    /// the disparity image.
+    ///
+    /// ## Arguments
+    /// * `disparity` - `32FC1` disparity image aligned with the rectified left image
+    ///
+    /// ## Returns
+    /// * `32FC1` depth `CvImage` object
+    pub fn disparity_image_to_depth(&self, disparity: &CvImage) -> Result<CvImage, Box<dyn Error>> {
+        if disparity.encoding() != "32FC1" {
+            Err(format!("Unsupported encoding for disparity image [encoding: {}]", disparity.encoding()))?
+        }
+
+        let data = match disparity.as_container() {
+            DataContainer::VecF32(data) => data,
+            _ => Err("Unsupported container type".to_string())?
+        };
+
+        let cx_offset = self.left.cx() - self.right.cx();
+        let depth = data.iter()
+            .map(|d| {
+                let corrected = *d as f64 - cx_offset;
+                match corrected.is_finite() && corrected > 0.0 {
+                    true => (-self.right.tx() / corrected) as f32,
+                    false => f32::NAN,
+                }
+            })
+            .collect();
+
+        Ok(CvImage::from_parts(
+            disparity.header().clone(),
+            disparity.height(),
+            disparity.width(),
+            "32FC1",
+            DataContainer::VecF32(depth)
+        ))
+    }
+}
@@ -0,0 +1,225 @@
+//! image_interop module contains conversions between `CvImage` and the
+//! [`image`](https://docs.rs/image) crate's `DynamicImage` and `ImageBuffer` types.
+//! This module is only available when the `image` feature is enabled.
+//!
+//! ## Supported encodings
+//! * `mono8` <-> `GrayImage`
+//! * `mono16` <-> `ImageBuffer<Luma<u16>, Vec<u16>>`
+//! * `rgb8`, `bgr8` <-> `RgbImage`
+//! * `rgba8`, `bgra8` <-> `RgbaImage`
+//! * `rgb16`, `bgr16` <-> `ImageBuffer<Rgb<u16>, Vec<u16>>`
+//!
+//! The `image` crate always stores color channels in RGB order, so BGR encoded
+//! images are swapped to RGB when converting to the `image` types. Conversions
+//! from the `image` types always produce RGB encoded images.
+
+use image::{
+    DynamicImage,
+    ImageBuffer,
+    GrayImage,
+    RgbImage,
+    RgbaImage,
+    Luma,
+    Rgb,
+    Pixel,
+};
+use std::convert::TryFrom;
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::msgs::std_msgs::Header;
+use crate::utils::image_byteorder_ops;
+
+type Gray16Image = ImageBuffer<Luma<u16>, Vec<u16>>;
+type Rgb16Image = ImageBuffer<Rgb<u16>, Vec<u16>>;
+type PixelBuffer<P> = ImageBuffer<P, Vec<<P as Pixel>::Subpixel>>;
+
+/// Takes the container out as a byte vector. Used for 8-bit encodings.
+fn into_u8_data(data: DataContainer) -> Result<Vec<u8>, Box<dyn Error>> {
+    match data {
+        DataContainer::VecU8(data) => Ok(data),
+        _ => Err("Unsupported container type".to_string())?
+    }
+}
+
+/// Takes the container out as a u16 vector. Used for 16-bit encodings.
+fn into_u16_data(data: DataContainer) -> Result<Vec<u16>, Box<dyn Error>> {
+    match data {
+        DataContainer::VecU8(data) => Ok(image_byteorder_ops::from_u8_to_u16(&data, false)),
+        DataContainer::VecU16(data) => Ok(data),
+        _ => Err("Unsupported container type".to_string())?
+    }
+}
+
+/// Swaps the first and third channel of every pixel (BGR <-> RGB).
+fn swap_red_blue<T>(data: &mut [T], num_channels: usize) {
+    for pixel in data.chunks_exact_mut(num_channels) {
+        pixel.swap(0, 2);
+    }
+}
+
+/// Builds an `ImageBuffer` from the raw data, failing if the data does not
+/// match the image dimensions.
+fn into_image_buffer<P: Pixel>(width: usize, height: usize, data: Vec<P::Subpixel>) -> Result<PixelBuffer<P>, Box<dyn Error>> {
+    match ImageBuffer::from_raw(width as u32, height as u32, data) {
+        Some(buffer) => Ok(buffer),
+        None => Err(format!("Image data does not match dimensions [width: {}, height: {}]", width, height))?
+    }
+}
+
+impl TryFrom<CvImage> for GrayImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let (_, height, width, encoding, data) = cv_image.into_parts();
+
+        match encoding.as_str() {
+            "mono8" => into_image_buffer(width, height, into_u8_data(data)?),
+            _ => Err(format!("Unsupported encoding for GrayImage [encoding: {}]", encoding))?
+        }
+    }
+}
+
+impl TryFrom<CvImage> for Gray16Image {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let (_, height, width, encoding, data) = cv_image.into_parts();
+
+        match encoding.as_str() {
+            "mono16" => into_image_buffer(width, height, into_u16_data(data)?),
+            _ => Err(format!("Unsupported encoding for ImageBuffer<Luma<u16>> [encoding: {}]", encoding))?
+        }
+    }
+}
+
+impl TryFrom<CvImage> for RgbImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let (_, height, width, encoding, data) = cv_image.into_parts();
+        let mut data = into_u8_data(data)?;
+
+        match encoding.as_str() {
+            "rgb8" => (),
+            "bgr8" => swap_red_blue(&mut data, 3),
+            _ => Err(format!("Unsupported encoding for RgbImage [encoding: {}]", encoding))?
+        };
+
+        into_image_buffer(width, height, data)
+    }
+}
+
+impl TryFrom<CvImage> for RgbaImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let (_, height, width, encoding, data) = cv_image.into_parts();
+        let mut data = into_u8_data(data)?;
+
+        match encoding.as_str() {
+            "rgba8" => (),
+            "bgra8" => swap_red_blue(&mut data, 4),
+            _ => Err(format!("Unsupported encoding for RgbaImage [encoding: {}]", encoding))?
+        };
+
+        into_image_buffer(width, height, data)
+    }
+}
+
+impl TryFrom<CvImage> for Rgb16Image {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let (_, height, width, encoding, data) = cv_image.into_parts();
+        let mut data = into_u16_data(data)?;
+
+        match encoding.as_str() {
+            "rgb16" => (),
+            "bgr16" => swap_red_blue(&mut data, 3),
+            _ => Err(format!("Unsupported encoding for ImageBuffer<Rgb<u16>> [encoding: {}]", encoding))?
+        };
+
+        into_image_buffer(width, height, data)
+    }
+}
+
+impl TryFrom<CvImage> for DynamicImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(cv_image: CvImage) -> Result<Self, Self::Error> {
+        let image = match cv_image.encoding().as_str() {
+            "mono8" => DynamicImage::ImageLuma8(GrayImage::try_from(cv_image)?),
+            "mono16" => DynamicImage::ImageLuma16(Gray16Image::try_from(cv_image)?),
+            "rgb8" | "bgr8" => DynamicImage::ImageRgb8(RgbImage::try_from(cv_image)?),
+            "rgba8" | "bgra8" => DynamicImage::ImageRgba8(RgbaImage::try_from(cv_image)?),
+            "rgb16" | "bgr16" => DynamicImage::ImageRgb16(Rgb16Image::try_from(cv_image)?),
+            encoding => Err(format!("Unsupported encoding for DynamicImage [encoding: {}]", encoding))?
+        };
+
+        Ok(image)
+    }
+}
+
+impl From<GrayImage> for CvImage {
+    fn from(image: GrayImage) -> Self {
+        let (width, height) = image.dimensions();
+        let data = DataContainer::VecU8(image.into_raw());
+
+        CvImage::from_parts(Header::default(), height as usize, width as usize, "mono8", data)
+    }
+}
+
+impl From<Gray16Image> for CvImage {
+    fn from(image: Gray16Image) -> Self {
+        let (width, height) = image.dimensions();
+        let data = DataContainer::VecU8(image_byteorder_ops::from_u16_to_u8(&image.into_raw(), false));
+
+        CvImage::from_parts(Header::default(), height as usize, width as usize, "mono16", data)
+    }
+}
+
+impl From<RgbImage> for CvImage {
+    fn from(image: RgbImage) -> Self {
+        let (width, height) = image.dimensions();
+        let data = DataContainer::VecU8(image.into_raw());
+
+        CvImage::from_parts(Header::default(), height as usize, width as usize, "rgb8", data)
+    }
+}
+
+impl From<RgbaImage> for CvImage {
+    fn from(image: RgbaImage) -> Self {
+        let (width, height) = image.dimensions();
+        let data = DataContainer::VecU8(image.into_raw());
+
+        CvImage::from_parts(Header::default(), height as usize, width as usize, "rgba8", data)
+    }
+}
+
+impl From<Rgb16Image> for CvImage {
+    fn from(image: Rgb16Image) -> Self {
+        let (width, height) = image.dimensions();
+        let data = DataContainer::VecU8(image_byteorder_ops::from_u16_to_u8(&image.into_raw(), false));
+
+        CvImage::from_parts(Header::default(), height as usize, width as usize, "rgb16", data)
+    }
+}
+
+impl TryFrom<DynamicImage> for CvImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(image: DynamicImage) -> Result<Self, Self::Error> {
+        match image {
+            DynamicImage::ImageLuma8(image) => Ok(CvImage::from(image)),
+            DynamicImage::ImageLuma16(image) => Ok(CvImage::from(image)),
+            DynamicImage::ImageRgb8(image) => Ok(CvImage::from(image)),
+            DynamicImage::ImageRgba8(image) => Ok(CvImage::from(image)),
+            DynamicImage::ImageRgb16(image) => Ok(CvImage::from(image)),
+            image => Err(format!("Unsupported DynamicImage color type [color: {:?}]", image.color()))?
+        }
+    }
+}
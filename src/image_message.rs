@@ -0,0 +1,161 @@
+//! image_message module contains the `ImageMessage` trait which abstracts over
+//! the different `sensor_msgs::Image` types. `CvImage::from_imgmsg` and
+//! `CvImage::into_imgmsg` are generic over this trait, so the same bridge can be
+//! used with the messages in `msgs`, the ROS2 messages generated by `r2r` (with the
+//! `r2r` feature) or any user defined type with the same fields.
+//!
+//! # Example
+//! ```
+//! use std::error::Error;
+//! use cv_bridge::{
+//!     CvImage,
+//!     ImageMessage,
+//!     msgs::std_msgs::Header,
+//! };
+//!
+//! struct LoggedImage {
+//!     frame_id: String,
+//!     height: u32,
+//!     width: u32,
+//!     encoding: String,
+//!     data: Vec<u8>,
+//! }
+//!
+//! impl ImageMessage for LoggedImage {
+//!     fn header(&self) -> Result<Header, Box<dyn Error>> {
+//!         Ok(Header { frame_id: self.frame_id.clone(), ..Default::default() })
+//!     }
+//!     fn height(&self) -> u32 { self.height }
+//!     fn width(&self) -> u32 { self.width }
+//!     fn encoding(&self) -> &str { &self.encoding }
+//!     fn is_bigendian(&self) -> u8 { 0 }
+//!     fn step(&self) -> u32 { self.data.len() as u32 / self.height.max(1) }
+//!     fn data(&self) -> &[u8] { &self.data }
+//!     fn into_data(self) -> Vec<u8> { self.data }
+//!     fn from_fields(header: Header, height: u32, width: u32, encoding: String,
+//!                    _is_bigendian: u8, _step: u32, data: Vec<u8>) -> Result<Self, Box<dyn Error>> {
+//!         Ok(LoggedImage { frame_id: header.frame_id, height, width, encoding, data })
+//!     }
+//! }
+//!
+//! let logged = LoggedImage {
+//!     frame_id: "camera".to_string(),
+//!     height: 2,
+//!     width: 2,
+//!     encoding: "mono8".to_string(),
+//!     data: vec![0, 1, 2, 3],
+//! };
+//! let cv_image = CvImage::from_imgmsg(logged).unwrap();
+//! let logged: LoggedImage = cv_image.into_imgmsg(0).unwrap();
+//! ```
+
+use std::error::Error;
+
+use crate::msgs::{
+    std_msgs::Header,
+    sensor_msgs::Image,
+};
+
+/// Trait implemented by `sensor_msgs::Image` shaped messages
+pub trait ImageMessage: Sized {
+    /// Returns the header of the message as a `std_msgs::Header` message. Fails if
+    /// the header cannot be represented, e.g. a ROS2 stamp with negative seconds.
+    fn header(&self) -> Result<Header, Box<dyn Error>>;
+
+    /// Returns the image height, that is, number of rows
+    fn height(&self) -> u32;
+
+    /// Returns the image width, that is, number of columns
+    fn width(&self) -> u32;
+
+    /// Returns the encoding of the pixels. Check the supported encodings in the
+    /// `image_encodings` module.
+    fn encoding(&self) -> &str;
+
+    /// Returns the endianness of the image data. 0 for little-endian, 1 for big-endian.
+    fn is_bigendian(&self) -> u8;
+
+    /// Returns the full row length in bytes
+    fn step(&self) -> u32;
+
+    /// Returns the immutable image data
+    fn data(&self) -> &[u8];
+
+    /// Consumes the message and returns the image data
+    fn into_data(self) -> Vec<u8>;
+
+    /// Constructs a new message from its fields. Fails if the fields cannot be
+    /// represented in the message, e.g. a stamp that overflows a ROS2 stamp.
+    ///
+    /// ## Arguments
+    /// * `header` - `std_msgs::Header` message
+    /// * `height` - Image height, that is, number of rows
+    /// * `width` - Image width, that is, number of columns
+    /// * `encoding` - Encoding of the pixels
+    /// * `is_bigendian` - Endianness of the image data. 0 for little-endian, 1 for big-endian.
+    /// * `step` - Full row length in bytes
+    /// * `data` - Image data, size is (step * rows)
+    fn from_fields(
+        header: Header,
+        height: u32,
+        width: u32,
+        encoding: String,
+        is_bigendian: u8,
+        step: u32,
+        data: Vec<u8>
+    ) -> Result<Self, Box<dyn Error>>;
+}
+
+impl ImageMessage for Image {
+    fn header(&self) -> Result<Header, Box<dyn Error>> {
+        Ok(self.header.clone())
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn encoding(&self) -> &str {
+        &self.encoding
+    }
+
+    fn is_bigendian(&self) -> u8 {
+        self.is_bigendian
+    }
+
+    fn step(&self) -> u32 {
+        self.step
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn from_fields(
+        header: Header,
+        height: u32,
+        width: u32,
+        encoding: String,
+        is_bigendian: u8,
+        step: u32,
+        data: Vec<u8>
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Image {
+            header,
+            height,
+            width,
+            encoding,
+            is_bigendian,
+            step,
+            data,
+        })
+    }
+}
@@ -0,0 +1,135 @@
+//! crop module crops `CvImage`s by a `sensor_msgs::RegionOfInterest` and
+//! updates the `sensor_msgs::CameraInfo` of the cropped stream, like the
+//! `crop_decimate` nodelet of `image_proc`.
+//!
+//! Following the `sensor_msgs::CameraInfo` conventions, the calibration matrices
+//! are left untouched and the crop is recorded in the `roi` field, in full
+//! resolution (unbinned) coordinates. `PinholeCameraModel` then shifts the
+//! principal point by the ROI offset, so `Rectifier` and the projection functions
+//! keep working on the cropped images.
+
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use crate::msgs::sensor_msgs::{
+    CameraInfo,
+    RegionOfInterest,
+};
+use super::{
+    checked_pixel_len,
+    map_container,
+};
+
+/// Crops the image and updates its camera info. The output keeps the header
+/// and encoding of the input.
+///
+/// # Example
+/// ```ignore
+/// let roi = RegionOfInterest { x_offset: 100, y_offset: 50, width: 320, height: 240, do_rectify: false };
+/// let (cropped, cropped_info) = crop(&cv_image, &camera_info, &roi).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `image` - Image to crop
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `roi` - Region to keep, in pixels of the image. A zero `width` or `height`
+///   extends the region to the right or bottom edge of the image.
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the cropped image and its camera info or
+///   an error if the region is outside of the image or the image data does not
+///   match its dimensions
+pub fn crop(image: &CvImage, camera_info: &CameraInfo, roi: &RegionOfInterest) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    let (x, y, width, height) = resolve_roi(roi, image.width(), image.height())?;
+    let len = checked_pixel_len(image)?;
+
+    let row_len = image.width() * len;
+    let data = map_container!(image.as_container(), |data| {
+        data.chunks_exact(row_len)
+            .skip(y)
+            .take(height)
+            .flat_map(|row| row[x * len..(x + width) * len].iter().copied())
+            .collect()
+    });
+
+    let cropped = CvImage::from_parts(image.header().clone(), height, width, image.encoding(), data);
+    let cropped_info = crop_camera_info(camera_info, &RegionOfInterest {
+        x_offset: x as u32,
+        y_offset: y as u32,
+        height: height as u32,
+        width: width as u32,
+        do_rectify: roi.do_rectify,
+    });
+
+    Ok((cropped, cropped_info))
+}
+
+/// Returns a view of a region of the image without copying the pixels.
+///
+/// Like `CvImage::as_cvmat`, the returned `Mat` points to the data of the image
+/// and must not outlive it.
+///
+/// ## Arguments
+/// * `image` - Image to crop
+/// * `roi` - Region to keep, in pixels of the image. A zero `width` or `height`
+///   extends the region to the right or bottom edge of the image.
+///
+/// ## Returns
+/// * `opencv::core::Mat` view of the region or an error if the region is outside of the image
+#[cfg(feature = "opencv")]
+pub fn crop_view(image: &mut CvImage, roi: &RegionOfInterest) -> Result<opencv::core::Mat, Box<dyn Error>> {
+    let (x, y, width, height) = resolve_roi(roi, image.width(), image.height())?;
+    let mat = image.as_cvmat()?;
+    let rect = opencv::core::Rect::new(x as i32, y as i32, width as i32, height as i32);
+
+    Ok(opencv::core::Mat::roi(&mat, rect)?)
+}
+
+/// Returns the camera info of an image cropped by the region of interest. The
+/// region is composed with the ROI and binning already in the camera info.
+///
+/// ## Arguments
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the uncropped image
+/// * `roi` - Region kept, in pixels of the uncropped image. The width and height
+///   must be resolved, see `crop`.
+///
+/// ## Returns
+/// * `sensor_msgs::CameraInfo` message of the cropped image
+pub fn crop_camera_info(camera_info: &CameraInfo, roi: &RegionOfInterest) -> CameraInfo {
+    let binning_x = camera_info.binning_x.max(1);
+    let binning_y = camera_info.binning_y.max(1);
+
+    let mut cropped_info = camera_info.clone();
+    cropped_info.roi = RegionOfInterest {
+        x_offset: camera_info.roi.x_offset + roi.x_offset * binning_x,
+        y_offset: camera_info.roi.y_offset + roi.y_offset * binning_y,
+        height: roi.height * binning_y,
+        width: roi.width * binning_x,
+        do_rectify: roi.do_rectify,
+    };
+
+    cropped_info
+}
+
+/// Returns the `(x, y, width, height)` of the region, checking it against the image size
+fn resolve_roi(roi: &RegionOfInterest, image_width: usize, image_height: usize) -> Result<(usize, usize, usize, usize), Box<dyn Error>> {
+    let x = roi.x_offset as usize;
+    let y = roi.y_offset as usize;
+    let width = match roi.width {
+        0 => image_width.saturating_sub(x),
+        width => width as usize,
+    };
+    let height = match roi.height {
+        0 => image_height.saturating_sub(y),
+        height => height as usize,
+    };
+
+    if width == 0 || height == 0 || x + width > image_width || y + height > image_height {
+        Err(format!(
+            "Region of interest outside of the image [roi: {}x{}+{}+{}, image: {}x{}]",
+            roi.width, roi.height, roi.x_offset, roi.y_offset, image_width, image_height
+        ))?
+    }
+
+    Ok((x, y, width, height))
+}
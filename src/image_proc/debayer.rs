@@ -0,0 +1,133 @@
+//! debayer module demosaics Bayer `CvImage`s into color and monochrome images,
+//! like the `debayer` nodelet of `image_proc`.
+//!
+//! ## Outputs
+//! * `debayer` - `bgr8` or `bgr16` for Bayer input, `bgr8` for YUV input and the
+//!   input image unchanged for color and `mono` images
+//! * `debayer_mono` - `mono8` or `mono16` for Bayer and color input, `mono8` for
+//!   YUV input and the input image unchanged for `mono` images
+
+use opencv::prelude::*;
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use crate::utils::image_encodings::{
+    self,
+    Encoding,
+};
+
+/// Demosaicing algorithm used for color output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebayerAlgorithm {
+    /// Bilinear interpolation (`COLOR_Bayer*2BGR`)
+    Bilinear,
+    /// Edge-aware interpolation (`COLOR_Bayer*2BGR_EA`)
+    EdgeAware,
+    /// Variable number of gradients (`COLOR_Bayer*2BGR_VNG`). OpenCV only
+    /// supports it for 8-bit images, so 16-bit images fall back to `Bilinear`.
+    Vng,
+}
+
+/// Demosaics the image into a color image. The output keeps the header of the input.
+///
+/// # Example
+/// ```ignore
+/// let image_color = debayer(&mut cv_image, DebayerAlgorithm::EdgeAware).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `image` - Raw image from the camera
+/// * `algorithm` - Demosaicing algorithm
+///
+/// ## Returns
+/// * Color `CvImage` object
+pub fn debayer(image: &mut CvImage, algorithm: DebayerAlgorithm) -> Result<CvImage, Box<dyn Error>> {
+    let src_enc = image_encodings::from_encstr_to_cvenc(image.encoding())?;
+    let bit_depth = image_encodings::get_bit_depth(image.encoding());
+
+    match src_enc {
+        Encoding::BayerRGGB | Encoding::BayerBGGR | Encoding::BayerGBRG | Encoding::BayerGRBG => {
+            let algorithm = match (algorithm, bit_depth) {
+                (DebayerAlgorithm::Vng, 16) => DebayerAlgorithm::Bilinear,
+                (algorithm, _) => algorithm,
+            };
+            let code = bayer_to_bgr_code(src_enc, algorithm)?;
+            let encoding = match bit_depth {
+                16 => "bgr16",
+                _ => "bgr8",
+            };
+
+            convert(image, code, encoding)
+        },
+        Encoding::Yuv422 | Encoding::Yuv422yuy2 => {
+            let code = image_encodings::get_conversion_code(src_enc, Encoding::Bgr)?;
+            convert(image, code, "bgr8")
+        },
+        _ => Ok(image.clone()),
+    }
+}
+
+/// Demosaics the image into a monochrome image. The output keeps the header of the input.
+///
+/// ## Arguments
+/// * `image` - Raw image from the camera
+///
+/// ## Returns
+/// * Monochrome `CvImage` object
+pub fn debayer_mono(image: &mut CvImage) -> Result<CvImage, Box<dyn Error>> {
+    let src_enc = image_encodings::from_encstr_to_cvenc(image.encoding())?;
+    let encoding = match image_encodings::get_bit_depth(image.encoding()) {
+        16 => "mono16",
+        _ => "mono8",
+    };
+
+    match src_enc {
+        Encoding::Gray => Ok(image.clone()),
+        _ => {
+            let code = image_encodings::get_conversion_code(src_enc, Encoding::Gray)?;
+            convert(image, code, encoding)
+        },
+    }
+}
+
+/// Returns the color conversion code demosaicing the Bayer pattern into `bgr`.
+/// ROS names the pattern by its first row and OpenCV by its second one.
+///
+/// ## Arguments
+/// * `pattern` - Bayer pattern of the image
+/// * `algorithm` - Demosaicing algorithm
+///
+/// ## Returns
+/// * The conversion code (eg. opencv::imgproc::COLOR_BayerBG2BGR) or an error
+///   if the encoding is not a Bayer pattern
+pub fn bayer_to_bgr_code(pattern: Encoding, algorithm: DebayerAlgorithm) -> Result<i32, Box<dyn Error>> {
+    let code = match (&pattern, algorithm) {
+        (Encoding::BayerRGGB, DebayerAlgorithm::Bilinear) => opencv::imgproc::COLOR_BayerBG2BGR,
+        (Encoding::BayerBGGR, DebayerAlgorithm::Bilinear) => opencv::imgproc::COLOR_BayerRG2BGR,
+        (Encoding::BayerGBRG, DebayerAlgorithm::Bilinear) => opencv::imgproc::COLOR_BayerGR2BGR,
+        (Encoding::BayerGRBG, DebayerAlgorithm::Bilinear) => opencv::imgproc::COLOR_BayerGB2BGR,
+        (Encoding::BayerRGGB, DebayerAlgorithm::EdgeAware) => opencv::imgproc::COLOR_BayerBG2BGR_EA,
+        (Encoding::BayerBGGR, DebayerAlgorithm::EdgeAware) => opencv::imgproc::COLOR_BayerRG2BGR_EA,
+        (Encoding::BayerGBRG, DebayerAlgorithm::EdgeAware) => opencv::imgproc::COLOR_BayerGR2BGR_EA,
+        (Encoding::BayerGRBG, DebayerAlgorithm::EdgeAware) => opencv::imgproc::COLOR_BayerGB2BGR_EA,
+        (Encoding::BayerRGGB, DebayerAlgorithm::Vng) => opencv::imgproc::COLOR_BayerBG2BGR_VNG,
+        (Encoding::BayerBGGR, DebayerAlgorithm::Vng) => opencv::imgproc::COLOR_BayerRG2BGR_VNG,
+        (Encoding::BayerGBRG, DebayerAlgorithm::Vng) => opencv::imgproc::COLOR_BayerGR2BGR_VNG,
+        (Encoding::BayerGRBG, DebayerAlgorithm::Vng) => opencv::imgproc::COLOR_BayerGB2BGR_VNG,
+        _ => Err(format!("Not a Bayer pattern [encoding: {:?}]", pattern))?
+    };
+
+    Ok(code)
+}
+
+fn convert(image: &mut CvImage, code: i32, encoding: &str) -> Result<CvImage, Box<dyn Error>> {
+    let src_mat = image.as_cvmat()?;
+    let mut dst_mat = Mat::default();
+
+    opencv::imgproc::cvt_color(&src_mat, &mut dst_mat, code, 0)?;
+
+    let mut dst_image = CvImage::from_cvmat(dst_mat, encoding)?;
+    *dst_image.header_mut() = image.header().clone();
+
+    Ok(dst_image)
+}
@@ -0,0 +1,154 @@
+//! Image processing operations on `CvImage` which keep the accompanying
+//! `sensor_msgs::CameraInfo` consistent, in the same way as the `image_proc`
+//! ROS package. Operations which rely on OpenCV are only available with the
+//! `opencv` feature.
+
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use crate::utils::image_encodings;
+
+pub mod crop;
+#[cfg(feature = "opencv")]
+pub mod debayer;
+pub mod resize;
+pub mod rotate;
+#[cfg(feature = "opencv")]
+pub mod rectify;
+
+pub use crop::crop;
+pub use resize::{
+    decimate,
+    resize,
+};
+pub use rotate::{
+    flip_horizontal,
+    flip_vertical,
+    rotate90,
+    rotate180,
+    rotate270,
+};
+#[cfg(feature = "opencv")]
+pub use debayer::{
+    DebayerAlgorithm,
+    debayer,
+    debayer_mono,
+};
+#[cfg(feature = "opencv")]
+pub use rectify::Rectifier;
+
+/// Interpolation used when resampling an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Nearest,
+    Linear,
+    Cubic,
+    Area,
+    Lanczos4,
+}
+
+impl Interpolation {
+    /// Returns the OpenCV interpolation flag (eg. opencv::imgproc::INTER_LINEAR)
+    #[cfg(feature = "opencv")]
+    pub fn as_cv_flag(&self) -> i32 {
+        match self {
+            Interpolation::Nearest => opencv::imgproc::INTER_NEAREST,
+            Interpolation::Linear => opencv::imgproc::INTER_LINEAR,
+            Interpolation::Cubic => opencv::imgproc::INTER_CUBIC,
+            Interpolation::Area => opencv::imgproc::INTER_AREA,
+            Interpolation::Lanczos4 => opencv::imgproc::INTER_LANCZOS4,
+        }
+    }
+}
+
+/// Returns the number of container elements per pixel of the image, that is,
+/// bytes per pixel for `DataContainer::VecU8` and channels otherwise.
+pub(crate) fn pixel_len(image: &CvImage) -> Result<usize, Box<dyn Error>> {
+    let channels = image_encodings::get_num_channels(image.encoding());
+    if channels == 0 {
+        Err(format!("Unsupported encoding [encoding: {}]", image.encoding()))?
+    }
+
+    match image.as_container() {
+        crate::cv_image::DataContainer::VecU8(_) => Ok(channels * (image_encodings::get_bit_depth(image.encoding()) as usize / 8)),
+        _ => Ok(channels),
+    }
+}
+
+/// Returns the `pixel_len` of the image, failing if the length of the container
+/// is not `height * width * pixel_len`
+pub(crate) fn checked_pixel_len(image: &CvImage) -> Result<usize, Box<dyn Error>> {
+    let len = pixel_len(image)?;
+    let data_len = match image.as_container() {
+        crate::cv_image::DataContainer::VecU8(data) => data.len(),
+        crate::cv_image::DataContainer::VecI8(data) => data.len(),
+        crate::cv_image::DataContainer::VecU16(data) => data.len(),
+        crate::cv_image::DataContainer::VecI16(data) => data.len(),
+        crate::cv_image::DataContainer::VecI32(data) => data.len(),
+        crate::cv_image::DataContainer::VecF32(data) => data.len(),
+        crate::cv_image::DataContainer::VecF64(data) => data.len(),
+    };
+
+    let expected = image.height() * image.width() * len;
+    if data_len != expected {
+        Err(format!(
+            "Image data does not match dimensions [width: {}, height: {}, expected: {}, actual: {}]",
+            image.width(), image.height(), expected, data_len
+        ))?
+    }
+
+    Ok(len)
+}
+
+/// Checks that the pixels of the image can be moved around independently, which
+/// is not the case for Bayer and YUV images whose channels span several pixels
+pub(crate) fn check_encoding(image: &CvImage, operation: &str) -> Result<(), Box<dyn Error>> {
+    if image.encoding().starts_with("bayer_") || image.encoding().starts_with("yuv422") {
+        Err(format!("Unsupported encoding for {} [encoding: {}]", operation, image.encoding()))?
+    }
+
+    pixel_len(image)?;
+
+    Ok(())
+}
+
+/// Applies an expression to the data of a `DataContainer` whatever its element
+/// type, wrapping the result in the same variant.
+macro_rules! map_container {
+    ($container:expr, |$data:ident| $body:expr) => {
+        match $container {
+            $crate::cv_image::DataContainer::VecU8($data) => $crate::cv_image::DataContainer::VecU8($body),
+            $crate::cv_image::DataContainer::VecI8($data) => $crate::cv_image::DataContainer::VecI8($body),
+            $crate::cv_image::DataContainer::VecU16($data) => $crate::cv_image::DataContainer::VecU16($body),
+            $crate::cv_image::DataContainer::VecI16($data) => $crate::cv_image::DataContainer::VecI16($body),
+            $crate::cv_image::DataContainer::VecI32($data) => $crate::cv_image::DataContainer::VecI32($body),
+            $crate::cv_image::DataContainer::VecF32($data) => $crate::cv_image::DataContainer::VecF32($body),
+            $crate::cv_image::DataContainer::VecF64($data) => $crate::cv_image::DataContainer::VecF64($body),
+        }
+    };
+}
+
+pub(crate) use map_container;
+
+/// Builds an image of the given size by copying, for each output pixel, the input pixel given by `source`
+pub(crate) fn remap_pixels<F>(image: &CvImage, width: usize, height: usize, source: F) -> Result<CvImage, Box<dyn Error>>
+where
+    F: Fn(usize, usize) -> (usize, usize)
+{
+    let len = pixel_len(image)?;
+    let src_width = image.width();
+
+    let data = map_container!(image.as_container(), |data| {
+        let mut dst = Vec::with_capacity(width * height * len);
+        for y in 0..height {
+            for x in 0..width {
+                let (src_x, src_y) = source(x, y);
+                let start = (src_y * src_width + src_x) * len;
+                dst.extend_from_slice(&data[start..start + len]);
+            }
+        }
+        dst
+    });
+
+    Ok(CvImage::from_parts(image.header().clone(), height, width, image.encoding(), data))
+}
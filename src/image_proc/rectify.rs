@@ -0,0 +1,258 @@
+//! rectify module contains the `Rectifier` struct which undistorts and rectifies
+//! `CvImage`s using the distortion model in `sensor_msgs::CameraInfo`.
+//!
+//! The undistortion maps are computed with `cv::initUndistortRectifyMap` (or
+//! `cv::fisheye::initUndistortRectifyMap` for the `equidistant` model) the first
+//! time an image is processed and cached until the calibration changes, so a
+//! `Rectifier` should be kept around and reused for every frame of a stream.
+//!
+//! Bayer and YUV images must be converted first, and `Interpolation::Area` is
+//! not supported by `cv::remap`.
+//!
+//! ## Supported distortion models
+//! * `plumb_bob`
+//! * `rational_polynomial`
+//! * `equidistant`
+
+use opencv::prelude::*;
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use crate::image_geometry::{
+    PinholeCameraModel,
+    distortion::DistortionModel,
+};
+use crate::msgs::sensor_msgs::{
+    CameraInfo,
+    RegionOfInterest,
+};
+use super::{
+    Interpolation,
+    check_encoding,
+};
+
+/// Target of the cached undistortion maps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MapKind {
+    /// Undistort and rectify into the projection matrix `P`
+    Rectify,
+    /// Only undistort, keeping the intrinsic matrix `K`
+    Undistort,
+}
+
+/// Everything the undistortion maps depend on. The calibration matrices are
+/// compared by their bits, so a calibration with NaN coefficients still matches
+/// itself and the maps are not recomputed every frame.
+#[derive(Debug, Clone, PartialEq)]
+struct MapKey {
+    kind: MapKind,
+    height: u32,
+    width: u32,
+    distortion_model: String,
+    coeffs: Vec<u64>,
+    binning_x: u32,
+    binning_y: u32,
+    roi: RegionOfInterest,
+}
+
+impl MapKey {
+    fn new(camera_info: &CameraInfo, kind: MapKind) -> MapKey {
+        // The header changes every frame and does not affect the maps
+        let coeffs = camera_info.D.iter()
+            .chain(&camera_info.K)
+            .chain(&camera_info.R)
+            .chain(&camera_info.P)
+            .map(|value| value.to_bits())
+            .collect();
+
+        MapKey {
+            kind,
+            height: camera_info.height,
+            width: camera_info.width,
+            distortion_model: camera_info.distortion_model.clone(),
+            coeffs,
+            binning_x: camera_info.binning_x,
+            binning_y: camera_info.binning_y,
+            roi: camera_info.roi.clone(),
+        }
+    }
+}
+
+pub struct Rectifier {
+    interpolation: Interpolation,
+    cache_key: Option<MapKey>,
+    /// `(width, height)` of the cached maps
+    map_size: (usize, usize),
+    map1: Mat,
+    map2: Mat,
+}
+
+impl Rectifier {
+    /// Constructs a new `Rectifier`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut rectifier = Rectifier::new(Interpolation::Linear);
+    /// let image_rect = rectifier.rectify_image(&mut cv_image, &camera_info).unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `interpolation` - Interpolation used when remapping the image. `Interpolation::Area`
+    ///   is not supported and makes the rectification fail.
+    ///
+    /// ## Returns
+    /// * `Rectifier` object
+    pub fn new(interpolation: Interpolation) -> Rectifier {
+        Rectifier {
+            interpolation,
+            cache_key: None,
+            map_size: (0, 0),
+            map1: Mat::default(),
+            map2: Mat::default(),
+        }
+    }
+
+    /// Returns the interpolation used when remapping the image.
+    pub fn interpolation(&self) -> Interpolation {
+        self.interpolation
+    }
+
+    /// Sets the interpolation used when remapping the image. The cached maps
+    /// do not depend on the interpolation, so they are kept.
+    pub fn set_interpolation(&mut self, interpolation: Interpolation) {
+        self.interpolation = interpolation;
+    }
+
+    /// Undistorts and rectifies the image, producing the `image_rect` image of the
+    /// `image_proc` package. The output has the header and encoding of the input.
+    ///
+    /// ## Arguments
+    /// * `image` - Raw image from the camera
+    /// * `camera_info` - `sensor_msgs::CameraInfo` message of the camera
+    ///
+    /// ## Returns
+    /// * Rectified `CvImage` object
+    pub fn rectify_image(&mut self, image: &mut CvImage, camera_info: &CameraInfo) -> Result<CvImage, Box<dyn Error>> {
+        self.remap(image, camera_info, MapKind::Rectify)
+    }
+
+    /// Undistorts the image without rectifying it, so the output keeps the
+    /// intrinsic matrix `K` of the camera. The output has the header and encoding
+    /// of the input.
+    ///
+    /// ## Arguments
+    /// * `image` - Raw image from the camera
+    /// * `camera_info` - `sensor_msgs::CameraInfo` message of the camera
+    ///
+    /// ## Returns
+    /// * Undistorted `CvImage` object
+    pub fn undistort(&mut self, image: &mut CvImage, camera_info: &CameraInfo) -> Result<CvImage, Box<dyn Error>> {
+        self.remap(image, camera_info, MapKind::Undistort)
+    }
+
+    fn remap(&mut self, image: &mut CvImage, camera_info: &CameraInfo, kind: MapKind) -> Result<CvImage, Box<dyn Error>> {
+        check_encoding(image, "rectification")?;
+        if self.interpolation == Interpolation::Area {
+            Err("Area interpolation is not supported for rectification".to_string())?
+        }
+
+        self.update_maps(camera_info, kind)?;
+
+        if self.map_size != (image.width(), image.height()) {
+            Err(format!(
+                "Image size does not match camera info [image: {}x{}, camera info: {}x{}]",
+                image.width(), image.height(), self.map_size.0, self.map_size.1
+            ))?
+        }
+
+        let src_mat = image.as_cvmat()?;
+        let mut dst_mat = Mat::default();
+
+        opencv::imgproc::remap(
+            &src_mat,
+            &mut dst_mat,
+            &self.map1,
+            &self.map2,
+            self.interpolation.as_cv_flag(),
+            opencv::core::BORDER_CONSTANT,
+            opencv::core::Scalar::default()
+        )?;
+
+        let mut dst_image = CvImage::from_cvmat(dst_mat, image.encoding())?;
+        *dst_image.header_mut() = image.header().clone();
+
+        Ok(dst_image)
+    }
+
+    /// Recomputes the undistortion maps if the calibration or the map kind
+    /// changed since the last call.
+    fn update_maps(&mut self, camera_info: &CameraInfo, kind: MapKind) -> Result<(), Box<dyn Error>> {
+        let key = MapKey::new(camera_info, kind);
+        if self.cache_key.as_ref() == Some(&key) {
+            return Ok(());
+        }
+
+        let model = PinholeCameraModel::from_camera_info(camera_info)?;
+        let (width, height) = model.reduced_resolution();
+
+        let k = model.intrinsic_matrix();
+        let p = model.projection_matrix();
+        let identity = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+
+        let k_mat = Mat::from_slice_rows_cols(k, 3, 3)?;
+        let (r_mat, new_k_mat) = match kind {
+            MapKind::Rectify => (
+                Mat::from_slice_rows_cols(model.rotation_matrix(), 3, 3)?,
+                Mat::from_slice_rows_cols(&[p[0], p[1], p[2], p[4], p[5], p[6], p[8], p[9], p[10]], 3, 3)?,
+            ),
+            MapKind::Undistort => (
+                Mat::from_slice_rows_cols(&identity, 3, 3)?,
+                Mat::from_slice_rows_cols(k, 3, 3)?,
+            ),
+        };
+        let size = opencv::core::Size::new(width as i32, height as i32);
+
+        let mut map1 = Mat::default();
+        let mut map2 = Mat::default();
+
+        match model.distortion_model() {
+            Some(DistortionModel::Equidistant) => {
+                // The fisheye functions require exactly 4 coefficients
+                let mut d = [0.0; 4];
+                for (dst, src) in d.iter_mut().zip(model.distortion_coeffs()) {
+                    *dst = *src;
+                }
+
+                let d_mat = Mat::from_slice(&d)?;
+                opencv::calib3d::fisheye_init_undistort_rectify_map(
+                    &k_mat, &d_mat, &r_mat, &new_k_mat, size, opencv::core::CV_16SC2, &mut map1, &mut map2
+                )?;
+            },
+            Some(DistortionModel::PlumbBob) | Some(DistortionModel::RationalPolynomial) => {
+                let d_mat = Mat::from_slice(model.distortion_coeffs())?;
+                opencv::calib3d::init_undistort_rectify_map(
+                    &k_mat, &d_mat, &r_mat, &new_k_mat, size, opencv::core::CV_16SC2, &mut map1, &mut map2
+                )?;
+            },
+            None => {
+                let d_mat = Mat::default();
+                opencv::calib3d::init_undistort_rectify_map(
+                    &k_mat, &d_mat, &r_mat, &new_k_mat, size, opencv::core::CV_16SC2, &mut map1, &mut map2
+                )?;
+            },
+        }
+
+        self.map1 = map1;
+        self.map2 = map2;
+        self.map_size = (width as usize, height as usize);
+        self.cache_key = Some(key);
+
+        Ok(())
+    }
+}
+
+impl Default for Rectifier {
+    fn default() -> Self {
+        Rectifier::new(Interpolation::Linear)
+    }
+}
@@ -0,0 +1,271 @@
+//! resize module downscales `CvImage`s and updates the `sensor_msgs::CameraInfo`
+//! of the resized stream, like the `crop_decimate` and `resize` nodelets of
+//! `image_proc`.
+//!
+//! * `decimate` reduces the image by integer factors and records them in the
+//!   `binning_x` and `binning_y` fields, keeping the calibration matrices untouched.
+//! * `resize` scales the image to any size and scales `K` and `P` accordingly,
+//!   folding the existing ROI and binning into the matrices.
+//!
+//! `Nearest` and `Area` decimation and `Nearest` resizing are implemented in pure
+//! Rust, the other interpolations require the `opencv` feature. Bayer and YUV
+//! images must be converted before resizing, as resampling would mix their channels.
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::msgs::sensor_msgs::{
+    CameraInfo,
+    RegionOfInterest,
+};
+use crate::utils::{
+    image_byteorder_ops,
+    image_encodings,
+};
+use super::{
+    Interpolation,
+    check_encoding,
+    checked_pixel_len,
+    map_container,
+    remap_pixels,
+};
+
+/// Decimates the image by integer factors and updates the binning of its camera
+/// info. Rows and columns which do not fill a whole bin are dropped. The output
+/// keeps the header and encoding of the input.
+///
+/// # Example
+/// ```ignore
+/// let (decimated, decimated_info) = decimate(&mut cv_image, &camera_info, 2, 2, Interpolation::Area).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `image` - Image to decimate
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `decimation_x` - Horizontal decimation factor
+/// * `decimation_y` - Vertical decimation factor
+/// * `interpolation` - `Nearest` keeps the top-left pixel of each bin, `Area`
+///   averages the bin (pure binning)
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the decimated image and its camera info
+pub fn decimate(
+    image: &mut CvImage,
+    camera_info: &CameraInfo,
+    decimation_x: u32,
+    decimation_y: u32,
+    interpolation: Interpolation
+) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    check_encoding(image, "resizing")?;
+    checked_pixel_len(image)?;
+
+    if decimation_x == 0 || decimation_y == 0 {
+        Err(format!("Invalid decimation [decimation_x: {}, decimation_y: {}]", decimation_x, decimation_y))?
+    }
+
+    let (dx, dy) = (decimation_x as usize, decimation_y as usize);
+    let (width, height) = (image.width() / dx, image.height() / dy);
+    if width == 0 || height == 0 {
+        Err(format!(
+            "Decimation larger than the image [decimation: {}x{}, image: {}x{}]",
+            decimation_x, decimation_y, image.width(), image.height()
+        ))?
+    }
+
+    let decimated = match interpolation {
+        Interpolation::Nearest => remap_pixels(image, width, height, |x, y| (x * dx, y * dy))?,
+        Interpolation::Area => bin(image, dx, dy, width, height)?,
+        _ => cv_resize(image, width, height, interpolation)?,
+    };
+
+    let mut decimated_info = camera_info.clone();
+    decimated_info.binning_x = camera_info.binning_x.max(1) * decimation_x;
+    decimated_info.binning_y = camera_info.binning_y.max(1) * decimation_y;
+
+    Ok((decimated, decimated_info))
+}
+
+/// Resizes the image and scales the intrinsics of its camera info. The ROI and
+/// binning of the input camera info are folded into `K` and `P`, so the output
+/// camera info has no ROI nor binning and describes the resized image directly.
+/// The output keeps the header and encoding of the input.
+///
+/// # Example
+/// ```ignore
+/// let (resized, resized_info) = resize(&mut cv_image, &camera_info, 224, 224, Interpolation::Area).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `image` - Image to resize
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `width` - Width of the resized image
+/// * `height` - Height of the resized image
+/// * `interpolation` - Interpolation used when resampling the image
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the resized image and its camera info
+pub fn resize(
+    image: &mut CvImage,
+    camera_info: &CameraInfo,
+    width: usize,
+    height: usize,
+    interpolation: Interpolation
+) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    check_encoding(image, "resizing")?;
+    checked_pixel_len(image)?;
+
+    if width == 0 || height == 0 || image.width() == 0 || image.height() == 0 {
+        Err(format!("Invalid size [image: {}x{}, resized: {}x{}]", image.width(), image.height(), width, height))?
+    }
+
+    let resized = match interpolation {
+        Interpolation::Nearest => {
+            let (src_width, src_height) = (image.width(), image.height());
+            remap_pixels(image, width, height, |x, y| (x * src_width / width, y * src_height / height))?
+        },
+        _ => cv_resize(image, width, height, interpolation)?,
+    };
+
+    let resized_info = resized_camera_info(camera_info, (image.width(), image.height()), (width, height));
+
+    Ok((resized, resized_info))
+}
+
+/// Returns the camera info of an image resized from `src_size` to `dst_size`, with
+/// the ROI and binning folded into `K` and `P`
+pub(crate) fn resized_camera_info(camera_info: &CameraInfo, src_size: (usize, usize), dst_size: (usize, usize)) -> CameraInfo {
+    let (width, height) = dst_size;
+    let scale_x = width as f64 / src_size.0 as f64 / camera_info.binning_x.max(1) as f64;
+    let scale_y = height as f64 / src_size.1 as f64 / camera_info.binning_y.max(1) as f64;
+    let offset_x = camera_info.roi.x_offset as f64;
+    let offset_y = camera_info.roi.y_offset as f64;
+
+    let mut resized_info = camera_info.clone();
+    resized_info.width = width as u32;
+    resized_info.height = height as u32;
+    resized_info.binning_x = 0;
+    resized_info.binning_y = 0;
+    resized_info.roi = RegionOfInterest::default();
+
+    let k = &mut resized_info.K;
+    k[0] *= scale_x;
+    k[1] *= scale_x;
+    k[2] = (k[2] - offset_x) * scale_x;
+    k[4] *= scale_y;
+    k[5] = (k[5] - offset_y) * scale_y;
+
+    let p = &mut resized_info.P;
+    p[0] *= scale_x;
+    p[1] *= scale_x;
+    p[2] = (p[2] - offset_x) * scale_x;
+    p[3] *= scale_x;
+    p[5] *= scale_y;
+    p[6] = (p[6] - offset_y) * scale_y;
+    p[7] *= scale_y;
+
+    resized_info
+}
+
+/// Averages `dx` x `dy` bins of pixels
+fn bin(image: &CvImage, dx: usize, dy: usize, width: usize, height: usize) -> Result<CvImage, Box<dyn Error>> {
+    let channels = image_encodings::get_num_channels(image.encoding());
+    let src_width = image.width();
+    let bin = |data: &[f64]| bin_samples(data, channels, src_width, dx, dy, width, height);
+
+    let data = match image.as_container() {
+        DataContainer::VecU8(data) if image_encodings::get_bit_depth(image.encoding()) == 16 => {
+            let samples = image_byteorder_ops::from_u8_to_u16(data, false);
+            let binned: Vec<u16> = from_samples(&bin(&to_samples(&samples)));
+            DataContainer::VecU8(image_byteorder_ops::from_u16_to_u8(&binned, false))
+        },
+        container => map_container!(container, |data| from_samples(&bin(&to_samples(data)))),
+    };
+
+    Ok(CvImage::from_parts(image.header().clone(), height, width, image.encoding(), data))
+}
+
+fn bin_samples(data: &[f64], channels: usize, src_width: usize, dx: usize, dy: usize, width: usize, height: usize) -> Vec<f64> {
+    let count = (dx * dy) as f64;
+    let mut dst = vec![0.0; width * height * channels];
+
+    for y in 0..height * dy {
+        for x in 0..width * dx {
+            let src = (y * src_width + x) * channels;
+            let dst_index = ((y / dy) * width + x / dx) * channels;
+            for c in 0..channels {
+                dst[dst_index + c] += data[src + c];
+            }
+        }
+    }
+
+    dst.iter_mut().for_each(|value| *value /= count);
+    dst
+}
+
+/// Numeric type of the elements of a `DataContainer`
+trait Sample: Copy {
+    fn to_f64(self) -> f64;
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! impl_sample {
+    ($($t:ty => $from:expr),* $(,)?) => {
+        $(impl Sample for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                #[allow(clippy::redundant_closure_call)]
+                ($from)(value)
+            }
+        })*
+    };
+}
+
+// Integer samples are rounded, the `as` cast saturates to the type range
+impl_sample!(
+    u8 => |v: f64| v.round() as u8,
+    i8 => |v: f64| v.round() as i8,
+    u16 => |v: f64| v.round() as u16,
+    i16 => |v: f64| v.round() as i16,
+    u32 => |v: f64| v.round() as u32,
+    f32 => |v: f64| v as f32,
+    f64 => |v: f64| v,
+);
+
+fn to_samples<T: Sample>(data: &[T]) -> Vec<f64> {
+    data.iter().map(|value| value.to_f64()).collect()
+}
+
+fn from_samples<T: Sample>(data: &[f64]) -> Vec<T> {
+    data.iter().map(|value| T::from_f64(*value)).collect()
+}
+
+#[cfg(feature = "opencv")]
+fn cv_resize(image: &mut CvImage, width: usize, height: usize, interpolation: Interpolation) -> Result<CvImage, Box<dyn Error>> {
+    let src_mat = image.as_cvmat()?;
+    let mut dst_mat = opencv::core::Mat::default();
+
+    opencv::imgproc::resize(
+        &src_mat,
+        &mut dst_mat,
+        opencv::core::Size::new(width as i32, height as i32),
+        0.0,
+        0.0,
+        interpolation.as_cv_flag()
+    )?;
+
+    let mut dst_image = CvImage::from_cvmat(dst_mat, image.encoding())?;
+    *dst_image.header_mut() = image.header().clone();
+
+    Ok(dst_image)
+}
+
+#[cfg(not(feature = "opencv"))]
+fn cv_resize(_image: &mut CvImage, _width: usize, _height: usize, interpolation: Interpolation) -> Result<CvImage, Box<dyn Error>> {
+    Err(format!("Interpolation requires the `opencv` feature [interpolation: {:?}]", interpolation))?
+}
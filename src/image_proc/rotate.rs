@@ -0,0 +1,241 @@
+//! rotate module rotates and flips `CvImage`s by multiples of 90 degrees and
+//! transforms the `sensor_msgs::CameraInfo` of the stream accordingly, for
+//! cameras mounted upside down or sideways.
+//!
+//! The camera frame of the output is the input frame rotated about the optical
+//! axis (or mirrored for flips), so the intrinsics are permuted, the rectification
+//! rotation is expressed in the new frame and the tangential distortion
+//! coefficients are permuted. The ROI and binning of the input camera info are
+//! folded into `K` and `P`. As the output frame differs from the input frame, the
+//! functions can rewrite `header.frame_id` to a frame published for the rotated camera.
+
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use crate::msgs::sensor_msgs::CameraInfo;
+use super::{
+    check_encoding,
+    checked_pixel_len,
+    remap_pixels,
+    resize::resized_camera_info,
+};
+
+/// Rearrangement of the pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Orientation {
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl Orientation {
+    /// Returns the 2x2 linear part of the transform from input to output pixels,
+    /// which is also the transform of the x and y axes of the camera frame
+    fn linear(&self) -> [[f64; 2]; 2] {
+        match self {
+            Orientation::Rotate90 => [[0.0, -1.0], [1.0, 0.0]],
+            Orientation::Rotate180 => [[-1.0, 0.0], [0.0, -1.0]],
+            Orientation::Rotate270 => [[0.0, 1.0], [-1.0, 0.0]],
+            Orientation::FlipHorizontal => [[-1.0, 0.0], [0.0, 1.0]],
+            Orientation::FlipVertical => [[1.0, 0.0], [0.0, -1.0]],
+        }
+    }
+
+    /// Returns the `(width, height)` of the output image
+    fn output_size(&self, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Orientation::Rotate90 | Orientation::Rotate270 => (height, width),
+            _ => (width, height),
+        }
+    }
+
+    /// Returns the input pixel of an output pixel
+    fn source(&self, x: usize, y: usize, width: usize, height: usize) -> (usize, usize) {
+        match self {
+            Orientation::Rotate90 => (y, height - 1 - x),
+            Orientation::Rotate180 => (width - 1 - x, height - 1 - y),
+            Orientation::Rotate270 => (width - 1 - y, x),
+            Orientation::FlipHorizontal => (width - 1 - x, y),
+            Orientation::FlipVertical => (x, height - 1 - y),
+        }
+    }
+
+    /// Returns the tangential distortion coefficients `(p1, p2)` in the output frame
+    fn tangential(&self, p1: f64, p2: f64) -> (f64, f64) {
+        match self {
+            Orientation::Rotate90 => (p2, -p1),
+            Orientation::Rotate180 => (-p1, -p2),
+            Orientation::Rotate270 => (-p2, p1),
+            Orientation::FlipHorizontal => (p1, -p2),
+            Orientation::FlipVertical => (-p1, p2),
+        }
+    }
+}
+
+/// Rotates the image by 90 degrees clockwise and transforms its camera info.
+///
+/// # Example
+/// ```ignore
+/// let (rotated, rotated_info) = rotate90(&cv_image, &camera_info, Some("camera_rotated_optical")).unwrap();
+/// ```
+///
+/// ## Arguments
+/// * `image` - Image to rotate
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `frame_id` - Frame id written to the output headers, or `None` to keep the input one
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the rotated image and its camera info
+pub fn rotate90(image: &CvImage, camera_info: &CameraInfo, frame_id: Option<&str>) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    reorient(image, camera_info, frame_id, Orientation::Rotate90)
+}
+
+/// Rotates the image by 180 degrees and transforms its camera info.
+///
+/// ## Arguments
+/// * `image` - Image to rotate
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `frame_id` - Frame id written to the output headers, or `None` to keep the input one
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the rotated image and its camera info
+pub fn rotate180(image: &CvImage, camera_info: &CameraInfo, frame_id: Option<&str>) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    reorient(image, camera_info, frame_id, Orientation::Rotate180)
+}
+
+/// Rotates the image by 270 degrees clockwise (90 degrees counterclockwise) and
+/// transforms its camera info.
+///
+/// ## Arguments
+/// * `image` - Image to rotate
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `frame_id` - Frame id written to the output headers, or `None` to keep the input one
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the rotated image and its camera info
+pub fn rotate270(image: &CvImage, camera_info: &CameraInfo, frame_id: Option<&str>) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    reorient(image, camera_info, frame_id, Orientation::Rotate270)
+}
+
+/// Mirrors the image left to right and transforms its camera info. The output
+/// camera frame is mirrored, that is, its x axis is reversed.
+///
+/// ## Arguments
+/// * `image` - Image to flip
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `frame_id` - Frame id written to the output headers, or `None` to keep the input one
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the flipped image and its camera info
+pub fn flip_horizontal(image: &CvImage, camera_info: &CameraInfo, frame_id: Option<&str>) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    reorient(image, camera_info, frame_id, Orientation::FlipHorizontal)
+}
+
+/// Mirrors the image top to bottom and transforms its camera info. The output
+/// camera frame is mirrored, that is, its y axis is reversed.
+///
+/// ## Arguments
+/// * `image` - Image to flip
+/// * `camera_info` - `sensor_msgs::CameraInfo` message of the image
+/// * `frame_id` - Frame id written to the output headers, or `None` to keep the input one
+///
+/// ## Returns
+/// * `(image, camera_info)` tuple of the flipped image and its camera info
+pub fn flip_vertical(image: &CvImage, camera_info: &CameraInfo, frame_id: Option<&str>) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    reorient(image, camera_info, frame_id, Orientation::FlipVertical)
+}
+
+fn reorient(
+    image: &CvImage,
+    camera_info: &CameraInfo,
+    frame_id: Option<&str>,
+    orientation: Orientation
+) -> Result<(CvImage, CameraInfo), Box<dyn Error>> {
+    check_encoding(image, "rotation")?;
+    checked_pixel_len(image)?;
+
+    let (width, height) = (image.width(), image.height());
+    let (out_width, out_height) = orientation.output_size(width, height);
+
+    let mut out_image = remap_pixels(image, out_width, out_height, |x, y| orientation.source(x, y, width, height))?;
+    let mut out_info = reorient_camera_info(camera_info, (width, height), orientation);
+
+    if let Some(frame_id) = frame_id {
+        out_image.header_mut().frame_id = frame_id.to_string();
+        out_info.header.frame_id = frame_id.to_string();
+    }
+
+    Ok((out_image, out_info))
+}
+
+fn reorient_camera_info(camera_info: &CameraInfo, size: (usize, usize), orientation: Orientation) -> CameraInfo {
+    let (width, height) = size;
+    let mut info = resized_camera_info(camera_info, size, size);
+
+    let (out_width, out_height) = orientation.output_size(width, height);
+    info.width = out_width as u32;
+    info.height = out_height as u32;
+
+    // Pixel transform A, mapping the input pixel centers onto the output ones
+    let l = orientation.linear();
+    let offset_x = match l[0][0] + l[0][1] < 0.0 {
+        true => out_width as f64 - 1.0,
+        false => 0.0,
+    };
+    let offset_y = match l[1][0] + l[1][1] < 0.0 {
+        true => out_height as f64 - 1.0,
+        false => 0.0,
+    };
+    let a = [
+        l[0][0], l[0][1], offset_x,
+        l[1][0], l[1][1], offset_y,
+        0.0, 0.0, 1.0,
+    ];
+    // Camera frame transform T and its inverse, which is its transpose
+    let t = [
+        l[0][0], l[0][1], 0.0,
+        l[1][0], l[1][1], 0.0,
+        0.0, 0.0, 1.0,
+    ];
+    let t_inv = transpose(&t);
+
+    info.K = multiply::<9>(&multiply::<9>(&a, &info.K), &t_inv);
+    info.R = multiply::<9>(&multiply::<9>(&t, &info.R), &t_inv);
+
+    // P' = A * P * blkdiag(T^-1, 1)
+    let mut t_inv_4 = [0.0; 16];
+    for row in 0..3 {
+        t_inv_4[row * 4..row * 4 + 3].copy_from_slice(&t_inv[row * 3..row * 3 + 3]);
+    }
+    t_inv_4[15] = 1.0;
+    info.P = multiply::<12>(&multiply::<12>(&a, &info.P), &t_inv_4);
+
+    let fisheye = matches!(info.distortion_model.as_str(), "equidistant" | "fisheye");
+    if !fisheye && info.D.len() >= 4 {
+        let (p1, p2) = orientation.tangential(info.D[2], info.D[3]);
+        info.D[2] = p1;
+        info.D[3] = p2;
+    }
+
+    info
+}
+
+/// Multiplies two row-major matrices with 3 rows on the left, returning the
+/// 3xM product as an array of `N = 3 * M` elements
+fn multiply<const N: usize>(lhs: &[f64], rhs: &[f64]) -> [f64; N] {
+    let inner = lhs.len() / 3;
+    let cols = N / 3;
+    let mut out = [0.0; N];
+    for row in 0..3 {
+        for col in 0..cols {
+            out[row * cols + col] = (0..inner).map(|i| lhs[row * inner + i] * rhs[i * cols + col]).sum();
+        }
+    }
+    out
+}
+
+fn transpose(m: &[f64; 9]) -> [f64; 9] {
+    [m[0], m[3], m[6], m[1], m[4], m[7], m[2], m[5], m[8]]
+}
@@ -0,0 +1,161 @@
+//! labels module colorizes label (segmentation) images, whose pixels are class or
+//! instance ids, into `bgr8` images for debug visualization, optionally blended
+//! over the color image the labels were computed from.
+//!
+//! ## Supported label encodings
+//! * `mono8`, `8UC1` - Label ids from 0 to 255
+//! * `mono16`, `16UC1` - Label ids from 0 to 65535
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+
+/// Palette mapping label ids to `[b, g, r]` colors
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum LabelPalette {
+    /// Colors generated from the bits of the label id, as in the PASCAL VOC
+    /// colormap. Label 0 is black and neighbouring ids get distinct colors.
+    #[default]
+    Deterministic,
+    /// User-supplied colors indexed by label id. Ids past the end of the palette
+    /// use the deterministic color.
+    Custom(Vec<[u8; 3]>),
+}
+
+impl LabelPalette {
+    /// Returns the `[b, g, r]` color of a label id.
+    ///
+    /// ## Arguments
+    /// * `label` - Label id
+    ///
+    /// ## Returns
+    /// * `[b, g, r]` color
+    pub fn color(&self, label: u16) -> [u8; 3] {
+        match self {
+            LabelPalette::Custom(colors) if (label as usize) < colors.len() => colors[label as usize],
+            _ => deterministic_color(label),
+        }
+    }
+}
+
+impl CvImage {
+    /// Colorizes a label image into a `bgr8` image. The output keeps the header of the input.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let colorized = labels.colorize_labels(&LabelPalette::Deterministic).unwrap();
+    /// let image: Image = colorized.into_imgmsg(0).unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `palette` - Colors of the label ids
+    ///
+    /// ## Returns
+    /// * `bgr8` `CvImage` object
+    pub fn colorize_labels(&self, palette: &LabelPalette) -> Result<CvImage, Box<dyn Error>> {
+        let data = label_ids(self)?.iter()
+            .flat_map(|label| palette.color(*label))
+            .collect();
+
+        Ok(CvImage::from_parts(self.header().clone(), self.height(), self.width(), "bgr8", DataContainer::VecU8(data)))
+    }
+
+    /// Colorizes a label image and blends it over a color image of the same size.
+    /// The output keeps the header of the color image.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let overlay = labels.overlay_labels(&color_image, &LabelPalette::Deterministic, 0.5, Some(0)).unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `color_image` - `mono8`, `bgr8`, `rgb8`, `bgra8` or `rgba8` image the labels belong to
+    /// * `palette` - Colors of the label ids
+    /// * `alpha` - Opacity of the label colors, from 0 (color image only) to 1 (labels only)
+    /// * `background` - Label id left transparent, usually 0, or `None` to blend every label
+    ///
+    /// ## Returns
+    /// * `bgr8` `CvImage` object
+    pub fn overlay_labels(
+        &self,
+        color_image: &CvImage,
+        palette: &LabelPalette,
+        alpha: f64,
+        background: Option<u16>
+    ) -> Result<CvImage, Box<dyn Error>> {
+        if self.width() != color_image.width() || self.height() != color_image.height() {
+            Err(format!(
+                "Label and color images have different sizes [labels: {}x{}, color: {}x{}]",
+                self.width(), self.height(), color_image.width(), color_image.height()
+            ))?
+        }
+        if !(0.0..=1.0).contains(&alpha) {
+            Err(format!("Alpha must be between 0 and 1 [alpha: {}]", alpha))?
+        }
+
+        let labels = label_ids(self)?;
+        let pixels = bgr_pixels(color_image)?;
+
+        let data = labels.iter()
+            .zip(pixels)
+            .flat_map(|(label, pixel)| match Some(*label) == background {
+                true => pixel,
+                false => {
+                    let color = palette.color(*label);
+                    [0, 1, 2].map(|c| (alpha * color[c] as f64 + (1.0 - alpha) * pixel[c] as f64).round() as u8)
+                },
+            })
+            .collect();
+
+        Ok(CvImage::from_parts(
+            color_image.header().clone(), color_image.height(), color_image.width(), "bgr8", DataContainer::VecU8(data)
+        ))
+    }
+}
+
+/// Returns the PASCAL VOC colormap color of a label id in `[b, g, r]` order
+fn deterministic_color(label: u16) -> [u8; 3] {
+    let (mut r, mut g, mut b) = (0u8, 0u8, 0u8);
+    let mut id = label;
+    for shift in (0..8).rev() {
+        r |= ((id & 1) as u8) << shift;
+        g |= (((id >> 1) & 1) as u8) << shift;
+        b |= (((id >> 2) & 1) as u8) << shift;
+        id >>= 3;
+    }
+
+    [b, g, r]
+}
+
+/// Returns the label id of each pixel
+fn label_ids(labels: &CvImage) -> Result<Vec<u16>, Box<dyn Error>> {
+    match (labels.encoding().as_str(), labels.as_container()) {
+        ("mono8" | "8UC1", DataContainer::VecU8(data)) => Ok(data.iter().map(|id| *id as u16).collect()),
+        ("mono16" | "16UC1", DataContainer::VecU8(data)) => Ok(data.chunks_exact(2)
+            .map(|bytes| u16::from_le_bytes([bytes[0], bytes[1]]))
+            .collect()),
+        _ => Err(format!("Unsupported encoding for label image [encoding: {}]", labels.encoding()))?
+    }
+}
+
+/// Returns the `[b, g, r]` color of each pixel
+fn bgr_pixels(image: &CvImage) -> Result<Vec<[u8; 3]>, Box<dyn Error>> {
+    let data = match image.as_container() {
+        DataContainer::VecU8(data) => data,
+        _ => Err(format!("Unsupported encoding for color image [encoding: {}]", image.encoding()))?
+    };
+
+    let pixels = match image.encoding().as_str() {
+        "mono8" => data.iter().map(|v| [*v; 3]).collect(),
+        "bgr8" => data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+        "rgb8" => data.chunks_exact(3).map(|p| [p[2], p[1], p[0]]).collect(),
+        "bgra8" => data.chunks_exact(4).map(|p| [p[0], p[1], p[2]]).collect(),
+        "rgba8" => data.chunks_exact(4).map(|p| [p[2], p[1], p[0]]).collect(),
+        _ => Err(format!("Unsupported encoding for color image [encoding: {}]", image.encoding()))?
+    };
+
+    Ok(pixels)
+}
@@ -1,17 +1,32 @@
 #![doc(html_favicon_url = "https://omkarkabadagi5823.github.io/cv_bridge/logo.ico")]
 #![doc(html_logo_url = "https://omkarkabadagi5823.github.io/cv_bridge/logo.png")]
 //! `cv-bridge` is a crate for converting between OpenCV and ROS Image.
-//! This works in conjunction to the [`ros_rust`](https://github.com/adnanademovic/rosrust) crate.
+//! This works in conjunction to the [`ros_rust`](https://github.com/adnanademovic/rosrust) crate,
+//! or with ROS2 through the [`r2r`](https://github.com/sequenceplanner/r2r) crate.
 //! 
 //! # Crate Status
 //! - Currently only supports the standard CV encodings
-//! - Currently only supports CV_8U and CV_16U channel depths
+//! - Currently only supports CV_8U, CV_16U and CV_32F channel depths
 //! - Does not support compressed images
 //! 
+//! # Features
+//! - `opencv` (default) - Enables conversions between `CvImage` and `opencv::core::Mat`.
+//!   Disable default features to use the pure-Rust core (message handling, byte order
+//!   fixes and encoding metadata) without a system OpenCV install
+//! - `rosrust` (default) - Generates the messages in [`msgs`] with `rosrust` for use with ROS1.
+//!   Without it, the messages are plain Rust structs with the same fields
+//! - `r2r` - Enables conversions between the messages in [`msgs`] and the ROS2
+//!   messages generated by `r2r`
+//! - `image` - Enables conversions between `CvImage` and the `image` crate's
+//!   `DynamicImage` and `ImageBuffer` types (see the `image_interop` module)
+//! - `yaml` - Enables reading and writing YAML calibration files in the
+//!   `camera_calibration_parsers` module (INI files are always supported)
+//! 
 //! # Examples
 //! 
 //! ## Convert from ROS Image to OpenCV Mat
-//! ```
+#![cfg_attr(all(feature = "opencv", feature = "rosrust"), doc = "```no_run")]
+#![cfg_attr(not(all(feature = "opencv", feature = "rosrust")), doc = "```ignore")]
 //! use opencv::highgui;
 //! use cv_bridge::{
 //!     CvImage,
@@ -45,6 +60,20 @@
 
 pub mod msgs;
 pub mod cv_image;
+pub mod image_message;
+pub mod image_geometry;
+pub mod camera_calibration_parsers;
+pub mod camera_info_manager;
+pub mod image_proc;
+pub mod depth_image_proc;
+pub mod labels;
+pub mod multi_array;
 pub mod utils;
+pub mod display;
+#[cfg(feature = "opencv")]
+pub mod annotate;
+#[cfg(feature = "image")]
+pub mod image_interop;
 
 pub use cv_image::CvImage;
+pub use image_message::ImageMessage;
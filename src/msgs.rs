@@ -1,8 +0,0 @@
-//! Manually generated ros messages
-
-rosrust::rosmsg_include!(
-    std_msgs / Header,
-    sensor_msgs / CameraInfo,
-    sensor_msgs / Image,
-    sensor_msgs / CompressedImage,
-);
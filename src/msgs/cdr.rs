@@ -0,0 +1,442 @@
+//! ROS2 CDR (de)serialization of the messages in `msgs`. This allows reading and
+//! writing ROS2 image messages (e.g. from a rosbag2 or a DDS sample) without any
+//! ROS installation.
+//!
+//! The layout follows the ROS2 message definitions, so the header stamp is
+//! serialized as `builtin_interfaces/Time` (`int32 sec`, `uint32 nanosec`) and the
+//! header has no sequence number. Deserialization accepts both little-endian and
+//! big-endian plain CDR, serialization always produces little-endian CDR. Stamps
+//! with negative seconds fail to deserialize, and stamps with seconds past
+//! `i32::MAX` fail to serialize.
+//!
+//! # Example
+//! ```
+//! use cv_bridge::{
+//!     CvImage,
+//!     msgs::cdr::CdrMessage,
+//!     msgs::sensor_msgs::Image,
+//! };
+//!
+//! let mut image = Image::default();
+//! image.height = 1;
+//! image.width = 2;
+//! image.encoding = "mono8".to_string();
+//! image.step = 2;
+//! image.data = vec![10, 20];
+//!
+//! let bytes = image.to_cdr().unwrap();
+//! let cv_image = CvImage::from_cdr(&bytes).unwrap();
+//! assert_eq!(cv_image.width(), 2);
+//! ```
+
+use byteorder::{
+    ByteOrder,
+    BigEndian,
+    LittleEndian,
+};
+use std::error::Error;
+
+use crate::cv_image::CvImage;
+use super::{
+    Time,
+    std_msgs::Header,
+    sensor_msgs::{
+        Image,
+        CompressedImage,
+        CameraInfo,
+        RegionOfInterest,
+    },
+};
+
+/// Representation identifier of big-endian plain CDR
+const REPRESENTATION_CDR_BE: [u8; 2] = [0x00, 0x00];
+/// Representation identifier of little-endian plain CDR
+const REPRESENTATION_CDR_LE: [u8; 2] = [0x00, 0x01];
+
+/// Trait implemented by the messages which can be (de)serialized from ROS2 CDR
+pub trait CdrMessage: Sized {
+    /// Deserializes the message from CDR bytes, including the 4 byte
+    /// encapsulation header.
+    ///
+    /// ## Arguments
+    /// * `bytes` - CDR serialized message
+    ///
+    /// ## Returns
+    /// * Deserialized message or an error if the bytes are malformed
+    fn from_cdr(bytes: &[u8]) -> Result<Self, Box<dyn Error>>;
+
+    /// Serializes the message to little-endian CDR bytes, including the 4 byte
+    /// encapsulation header.
+    ///
+    /// ## Returns
+    /// * CDR serialized message or an error if the message cannot be represented
+    fn to_cdr(&self) -> Result<Vec<u8>, Box<dyn Error>>;
+}
+
+/// Reads primitives from a CDR buffer, keeping track of the alignment
+struct CdrReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> CdrReader<'a> {
+    fn new(bytes: &'a [u8]) -> Result<Self, Box<dyn Error>> {
+        if bytes.len() < 4 {
+            Err(format!("CDR buffer too short for encapsulation header [length: {}]", bytes.len()))?
+        }
+
+        let big_endian = match [bytes[0], bytes[1]] {
+            REPRESENTATION_CDR_BE => true,
+            REPRESENTATION_CDR_LE => false,
+            representation => Err(format!("Unsupported CDR representation [representation: {:?}]", representation))?
+        };
+
+        Ok(CdrReader {
+            data: &bytes[4..],
+            pos: 0,
+            big_endian,
+        })
+    }
+
+    fn align(&mut self, alignment: usize) {
+        self.pos = self.pos.div_ceil(alignment) * alignment;
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        let end = match self.pos.checked_add(len) {
+            Some(end) if end <= self.data.len() => end,
+            _ => Err(format!("CDR buffer too short [position: {}, requested: {}, length: {}]", self.pos, len, self.data.len()))?
+        };
+
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_bool(&mut self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.read_u8()? != 0)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        self.align(4);
+        let bytes = self.read_bytes(4)?;
+
+        Ok(match self.big_endian {
+            true => BigEndian::read_u32(bytes),
+            false => LittleEndian::read_u32(bytes),
+        })
+    }
+
+    fn read_i32(&mut self) -> Result<i32, Box<dyn Error>> {
+        self.align(4);
+        let bytes = self.read_bytes(4)?;
+
+        Ok(match self.big_endian {
+            true => BigEndian::read_i32(bytes),
+            false => LittleEndian::read_i32(bytes),
+        })
+    }
+
+    fn read_f64(&mut self) -> Result<f64, Box<dyn Error>> {
+        self.align(8);
+        let bytes = self.read_bytes(8)?;
+
+        Ok(match self.big_endian {
+            true => BigEndian::read_f64(bytes),
+            false => LittleEndian::read_f64(bytes),
+        })
+    }
+
+    fn read_string(&mut self) -> Result<String, Box<dyn Error>> {
+        let len = self.read_u32()? as usize;
+        let bytes = self.read_bytes(len)?;
+
+        // The length includes the null terminator
+        let bytes = match bytes.split_last() {
+            Some((0, bytes)) => bytes,
+            Some(_) => Err("CDR string is not null terminated".to_string())?,
+            None => bytes,
+        };
+
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    fn read_u8_sequence(&mut self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let len = self.read_u32()? as usize;
+
+        Ok(self.read_bytes(len)?.to_vec())
+    }
+
+    fn read_f64_sequence(&mut self) -> Result<Vec<f64>, Box<dyn Error>> {
+        let len = self.read_u32()? as usize;
+        if len > self.data.len() / 8 {
+            Err(format!("CDR sequence longer than buffer [length: {}]", len))?
+        }
+
+        (0..len).map(|_| self.read_f64()).collect()
+    }
+
+    fn read_f64_array<const N: usize>(&mut self) -> Result<[f64; N], Box<dyn Error>> {
+        let mut array = [0.0; N];
+        for value in array.iter_mut() {
+            *value = self.read_f64()?;
+        }
+
+        Ok(array)
+    }
+}
+
+/// Writes primitives to a little-endian CDR buffer, keeping track of the alignment
+struct CdrWriter {
+    data: Vec<u8>,
+}
+
+impl CdrWriter {
+    fn new() -> Self {
+        // Encapsulation header is the representation identifier followed by two option bytes
+        let mut data = REPRESENTATION_CDR_LE.to_vec();
+        data.extend_from_slice(&[0x00, 0x00]);
+
+        CdrWriter {
+            data,
+        }
+    }
+
+    fn align(&mut self, alignment: usize) {
+        // The alignment is relative to the end of the encapsulation header
+        let len = (self.data.len() - 4).div_ceil(alignment) * alignment;
+        self.data.resize(len + 4, 0);
+    }
+
+    fn write_u8(&mut self, value: u8) {
+        self.data.push(value);
+    }
+
+    fn write_bool(&mut self, value: bool) {
+        self.data.push(value as u8);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.align(4);
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_i32(&mut self, value: i32) {
+        self.align(4);
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_f64(&mut self, value: f64) {
+        self.align(8);
+        self.data.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_string(&mut self, value: &str) {
+        self.write_u32(value.len() as u32 + 1);
+        self.data.extend_from_slice(value.as_bytes());
+        self.data.push(0);
+    }
+
+    fn write_u8_sequence(&mut self, values: &[u8]) {
+        self.write_u32(values.len() as u32);
+        self.data.extend_from_slice(values);
+    }
+
+    fn write_f64_sequence(&mut self, values: &[f64]) {
+        self.write_u32(values.len() as u32);
+        for value in values {
+            self.write_f64(*value);
+        }
+    }
+
+    fn write_f64_array(&mut self, values: &[f64]) {
+        for value in values {
+            self.write_f64(*value);
+        }
+    }
+}
+
+fn read_header(reader: &mut CdrReader) -> Result<Header, Box<dyn Error>> {
+    let sec = reader.read_i32()?;
+    let nanosec = reader.read_u32()?;
+    let frame_id = reader.read_string()?;
+
+    let sec = match u32::try_from(sec) {
+        Ok(sec) => sec,
+        Err(_) => Err(format!("Negative stamp seconds are not supported [sec: {}]", sec))?
+    };
+
+    Ok(Header {
+        seq: 0,
+        stamp: Time {
+            sec,
+            nsec: nanosec,
+        },
+        frame_id,
+    })
+}
+
+fn write_header(writer: &mut CdrWriter, header: &Header) -> Result<(), Box<dyn Error>> {
+    let sec = match i32::try_from(header.stamp.sec) {
+        Ok(sec) => sec,
+        Err(_) => Err(format!("Stamp seconds out of range for ROS2 [sec: {}]", header.stamp.sec))?
+    };
+
+    writer.write_i32(sec);
+    writer.write_u32(header.stamp.nsec);
+    writer.write_string(&header.frame_id);
+
+    Ok(())
+}
+
+fn read_roi(reader: &mut CdrReader) -> Result<RegionOfInterest, Box<dyn Error>> {
+    Ok(RegionOfInterest {
+        x_offset: reader.read_u32()?,
+        y_offset: reader.read_u32()?,
+        height: reader.read_u32()?,
+        width: reader.read_u32()?,
+        do_rectify: reader.read_bool()?,
+    })
+}
+
+fn write_roi(writer: &mut CdrWriter, roi: &RegionOfInterest) {
+    writer.write_u32(roi.x_offset);
+    writer.write_u32(roi.y_offset);
+    writer.write_u32(roi.height);
+    writer.write_u32(roi.width);
+    writer.write_bool(roi.do_rectify);
+}
+
+impl CdrMessage for Header {
+    fn from_cdr(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        read_header(&mut CdrReader::new(bytes)?)
+    }
+
+    fn to_cdr(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, self)?;
+
+        Ok(writer.data)
+    }
+}
+
+impl CdrMessage for Image {
+    fn from_cdr(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut reader = CdrReader::new(bytes)?;
+
+        Ok(Image {
+            header: read_header(&mut reader)?,
+            height: reader.read_u32()?,
+            width: reader.read_u32()?,
+            encoding: reader.read_string()?,
+            is_bigendian: reader.read_u8()?,
+            step: reader.read_u32()?,
+            data: reader.read_u8_sequence()?,
+        })
+    }
+
+    fn to_cdr(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, &self.header)?;
+        writer.write_u32(self.height);
+        writer.write_u32(self.width);
+        writer.write_string(&self.encoding);
+        writer.write_u8(self.is_bigendian);
+        writer.write_u32(self.step);
+        writer.write_u8_sequence(&self.data);
+
+        Ok(writer.data)
+    }
+}
+
+impl CdrMessage for CompressedImage {
+    fn from_cdr(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut reader = CdrReader::new(bytes)?;
+
+        Ok(CompressedImage {
+            header: read_header(&mut reader)?,
+            format: reader.read_string()?,
+            data: reader.read_u8_sequence()?,
+        })
+    }
+
+    fn to_cdr(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, &self.header)?;
+        writer.write_string(&self.format);
+        writer.write_u8_sequence(&self.data);
+
+        Ok(writer.data)
+    }
+}
+
+impl CdrMessage for CameraInfo {
+    fn from_cdr(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut reader = CdrReader::new(bytes)?;
+
+        Ok(CameraInfo {
+            header: read_header(&mut reader)?,
+            height: reader.read_u32()?,
+            width: reader.read_u32()?,
+            distortion_model: reader.read_string()?,
+            D: reader.read_f64_sequence()?,
+            K: reader.read_f64_array()?,
+            R: reader.read_f64_array()?,
+            P: reader.read_f64_array()?,
+            binning_x: reader.read_u32()?,
+            binning_y: reader.read_u32()?,
+            roi: read_roi(&mut reader)?,
+        })
+    }
+
+    fn to_cdr(&self) -> Result<Vec<u8>, Box<dyn Error>> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, &self.header)?;
+        writer.write_u32(self.height);
+        writer.write_u32(self.width);
+        writer.write_string(&self.distortion_model);
+        writer.write_f64_sequence(&self.D);
+        writer.write_f64_array(&self.K);
+        writer.write_f64_array(&self.R);
+        writer.write_f64_array(&self.P);
+        writer.write_u32(self.binning_x);
+        writer.write_u32(self.binning_y);
+        write_roi(&mut writer, &self.roi);
+
+        Ok(writer.data)
+    }
+}
+
+impl CvImage {
+    /// Constructs a new `CvImage` from a CDR serialized ROS2 `sensor_msgs/Image` message.
+    ///
+    /// ## Arguments
+    /// * `bytes` - CDR serialized `sensor_msgs/Image` message
+    ///
+    /// ## Returns
+    /// * `CvImage` object
+    pub fn from_cdr(bytes: &[u8]) -> Result<CvImage, Box<dyn Error>> {
+        CvImage::from_imgmsg(Image::from_cdr(bytes)?)
+    }
+
+    /// Converts the `CvImage` to a CDR serialized ROS2 `sensor_msgs/Image` message.
+    ///
+    /// ## Arguments
+    /// * `is_bigendian` - Endianness of the image data. 0 for little-endian, 1 for big-endian.
+    ///   Note that this is the endianness of the pixel data, the CDR
+    ///   encoding itself is always little-endian.
+    ///
+    /// ## Returns
+    /// * CDR serialized `sensor_msgs/Image` message
+    pub fn into_cdr(self, is_bigendian: u8) -> Result<Vec<u8>, Box<dyn Error>> {
+        let image: Image = self.into_imgmsg(is_bigendian)?;
+
+        image.to_cdr()
+    }
+}
@@ -0,0 +1,42 @@
+//! Manually generated ros messages
+//! 
+//! The message backend is selected with cargo features:
+//! * `rosrust` (default) - Messages are generated by `rosrust` and can be used
+//!   directly with ROS1 publishers and subscribers
+//! * without `rosrust` - Messages are plain Rust structs with the same fields,
+//!   which do not depend on any ROS installation
+//! * `r2r` - Adds conversions between these messages and the ROS2 messages
+//!   generated by [`r2r`](https://github.com/sequenceplanner/r2r)
+
+#[cfg(feature = "rosrust")]
+rosrust::rosmsg_include!(
+    std_msgs / Header,
+    std_msgs / Float32MultiArray,
+    std_msgs / UInt8MultiArray,
+    std_msgs / MultiArrayLayout,
+    std_msgs / MultiArrayDimension,
+    sensor_msgs / CameraInfo,
+    sensor_msgs / Image,
+    sensor_msgs / CompressedImage,
+    sensor_msgs / PointCloud2,
+    sensor_msgs / PointField,
+    sensor_msgs / SetCameraInfo,
+);
+
+#[cfg(feature = "rosrust")]
+pub use rosrust::Time;
+
+#[cfg(not(feature = "rosrust"))]
+mod plain;
+
+#[cfg(not(feature = "rosrust"))]
+pub use plain::{
+    Time,
+    std_msgs,
+    sensor_msgs,
+};
+
+pub mod cdr;
+
+#[cfg(feature = "r2r")]
+pub mod r2r;
@@ -0,0 +1,149 @@
+//! Backend-agnostic ros messages used when the `rosrust` feature is disabled.
+//! The structs mirror the fields of the messages generated by `rosrust` so that
+//! code using them compiles with either backend.
+
+/// ROS time with seconds and nanoseconds since the epoch
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Time {
+    pub sec: u32,
+    pub nsec: u32,
+}
+
+pub mod std_msgs {
+    use super::Time;
+
+    /// Standard metadata for higher-level stamped data types
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Header {
+        pub seq: u32,
+        pub stamp: Time,
+        pub frame_id: String,
+    }
+
+    /// Properties of one dimension of a multi-array
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct MultiArrayDimension {
+        pub label: String,
+        pub size: u32,
+        pub stride: u32,
+    }
+
+    /// Layout of the data of a multi-array
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct MultiArrayLayout {
+        pub dim: Vec<MultiArrayDimension>,
+        pub data_offset: u32,
+    }
+
+    /// Multi-array of 32-bit floats
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Float32MultiArray {
+        pub layout: MultiArrayLayout,
+        pub data: Vec<f32>,
+    }
+
+    /// Multi-array of 8-bit unsigned integers
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct UInt8MultiArray {
+        pub layout: MultiArrayLayout,
+        pub data: Vec<u8>,
+    }
+}
+
+#[allow(non_snake_case)]
+pub mod sensor_msgs {
+    use super::std_msgs::Header;
+
+    /// Uncompressed image
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct Image {
+        pub header: Header,
+        pub height: u32,
+        pub width: u32,
+        pub encoding: String,
+        pub is_bigendian: u8,
+        pub step: u32,
+        pub data: Vec<u8>,
+    }
+
+    /// Compressed image
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct CompressedImage {
+        pub header: Header,
+        pub format: String,
+        pub data: Vec<u8>,
+    }
+
+    /// Subwindow of an image
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct RegionOfInterest {
+        pub x_offset: u32,
+        pub y_offset: u32,
+        pub height: u32,
+        pub width: u32,
+        pub do_rectify: bool,
+    }
+
+    /// Meta information for a camera
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct CameraInfo {
+        pub header: Header,
+        pub height: u32,
+        pub width: u32,
+        pub distortion_model: String,
+        pub D: Vec<f64>,
+        pub K: [f64; 9],
+        pub R: [f64; 9],
+        pub P: [f64; 12],
+        pub binning_x: u32,
+        pub binning_y: u32,
+        pub roi: RegionOfInterest,
+    }
+
+    /// Description of one entry of the points of a `PointCloud2`
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct PointField {
+        pub name: String,
+        pub offset: u32,
+        pub datatype: u8,
+        pub count: u32,
+    }
+
+    impl PointField {
+        pub const INT8: u8 = 1;
+        pub const UINT8: u8 = 2;
+        pub const INT16: u8 = 3;
+        pub const UINT16: u8 = 4;
+        pub const INT32: u8 = 5;
+        pub const UINT32: u8 = 6;
+        pub const FLOAT32: u8 = 7;
+        pub const FLOAT64: u8 = 8;
+    }
+
+    /// Collection of N-dimensional points
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct PointCloud2 {
+        pub header: Header,
+        pub height: u32,
+        pub width: u32,
+        pub fields: Vec<PointField>,
+        pub is_bigendian: bool,
+        pub point_step: u32,
+        pub row_step: u32,
+        pub data: Vec<u8>,
+        pub is_dense: bool,
+    }
+
+    /// Request of the `sensor_msgs/SetCameraInfo` service
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct SetCameraInfoReq {
+        pub camera_info: CameraInfo,
+    }
+
+    /// Response of the `sensor_msgs/SetCameraInfo` service
+    #[derive(Clone, Debug, Default, PartialEq)]
+    pub struct SetCameraInfoRes {
+        pub success: bool,
+        pub status_message: String,
+    }
+}
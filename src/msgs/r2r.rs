@@ -0,0 +1,276 @@
+//! Conversions between the crate messages and the ROS2 messages generated by `r2r`.
+//! This module is only available when the `r2r` feature is enabled.
+//! 
+//! ROS2 headers do not have a sequence number, so `seq` is set to 0 when
+//! converting from ROS2 and dropped when converting to ROS2.
+//! 
+//! ROS2 stamps have signed seconds while ROS1 stamps have unsigned seconds, so
+//! the conversions of stamped messages fail for negative ROS2 seconds and for
+//! ROS1 seconds past `i32::MAX`.
+//! 
+//! The ROS2 `sensor_msgs::msg::Image` also implements `ImageMessage`, so it can be
+//! passed to `CvImage::from_imgmsg` and returned from `CvImage::into_imgmsg` directly.
+
+use std::error::Error;
+
+use r2r::{
+    builtin_interfaces::msg::Time as Ros2Time,
+    std_msgs::msg::Header as Ros2Header,
+    sensor_msgs::msg::{
+        Image as Ros2Image,
+        CompressedImage as Ros2CompressedImage,
+        CameraInfo as Ros2CameraInfo,
+        RegionOfInterest as Ros2RegionOfInterest,
+    },
+};
+
+use crate::image_message::ImageMessage;
+use super::{
+    Time,
+    std_msgs::Header,
+    sensor_msgs::{
+        Image,
+        CompressedImage,
+        CameraInfo,
+        RegionOfInterest,
+    },
+};
+
+impl TryFrom<Ros2Time> for Time {
+    type Error = Box<dyn Error>;
+
+    fn try_from(time: Ros2Time) -> Result<Self, Self::Error> {
+        let sec = match u32::try_from(time.sec) {
+            Ok(sec) => sec,
+            Err(_) => Err(format!("ROS2 stamp seconds out of range [sec: {}]", time.sec))?
+        };
+
+        Ok(Time {
+            sec,
+            nsec: time.nanosec,
+        })
+    }
+}
+
+impl TryFrom<Time> for Ros2Time {
+    type Error = Box<dyn Error>;
+
+    fn try_from(time: Time) -> Result<Self, Self::Error> {
+        let sec = match i32::try_from(time.sec) {
+            Ok(sec) => sec,
+            Err(_) => Err(format!("Stamp seconds out of range for ROS2 [sec: {}]", time.sec))?
+        };
+
+        Ok(Ros2Time {
+            sec,
+            nanosec: time.nsec,
+        })
+    }
+}
+
+impl TryFrom<Ros2Header> for Header {
+    type Error = Box<dyn Error>;
+
+    fn try_from(header: Ros2Header) -> Result<Self, Self::Error> {
+        Ok(Header {
+            seq: 0,
+            stamp: Time::try_from(header.stamp)?,
+            frame_id: header.frame_id,
+        })
+    }
+}
+
+impl TryFrom<Header> for Ros2Header {
+    type Error = Box<dyn Error>;
+
+    fn try_from(header: Header) -> Result<Self, Self::Error> {
+        Ok(Ros2Header {
+            stamp: Ros2Time::try_from(header.stamp)?,
+            frame_id: header.frame_id,
+        })
+    }
+}
+
+impl TryFrom<Ros2Image> for Image {
+    type Error = Box<dyn Error>;
+
+    fn try_from(image: Ros2Image) -> Result<Self, Self::Error> {
+        Ok(Image {
+            header: Header::try_from(image.header)?,
+            height: image.height,
+            width: image.width,
+            encoding: image.encoding,
+            is_bigendian: image.is_bigendian,
+            step: image.step,
+            data: image.data,
+        })
+    }
+}
+
+impl TryFrom<Image> for Ros2Image {
+    type Error = Box<dyn Error>;
+
+    fn try_from(image: Image) -> Result<Self, Self::Error> {
+        Ok(Ros2Image {
+            header: Ros2Header::try_from(image.header)?,
+            height: image.height,
+            width: image.width,
+            encoding: image.encoding,
+            is_bigendian: image.is_bigendian,
+            step: image.step,
+            data: image.data,
+        })
+    }
+}
+
+impl ImageMessage for Ros2Image {
+    fn header(&self) -> Result<Header, Box<dyn Error>> {
+        Header::try_from(self.header.clone())
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn encoding(&self) -> &str {
+        &self.encoding
+    }
+
+    fn is_bigendian(&self) -> u8 {
+        self.is_bigendian
+    }
+
+    fn step(&self) -> u32 {
+        self.step
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+
+    fn from_fields(
+        header: Header,
+        height: u32,
+        width: u32,
+        encoding: String,
+        is_bigendian: u8,
+        step: u32,
+        data: Vec<u8>
+    ) -> Result<Self, Box<dyn Error>> {
+        Ok(Ros2Image {
+            header: Ros2Header::try_from(header)?,
+            height,
+            width,
+            encoding,
+            is_bigendian,
+            step,
+            data,
+        })
+    }
+}
+
+impl TryFrom<Ros2CompressedImage> for CompressedImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(image: Ros2CompressedImage) -> Result<Self, Self::Error> {
+        Ok(CompressedImage {
+            header: Header::try_from(image.header)?,
+            format: image.format,
+            data: image.data,
+        })
+    }
+}
+
+impl TryFrom<CompressedImage> for Ros2CompressedImage {
+    type Error = Box<dyn Error>;
+
+    fn try_from(image: CompressedImage) -> Result<Self, Self::Error> {
+        Ok(Ros2CompressedImage {
+            header: Ros2Header::try_from(image.header)?,
+            format: image.format,
+            data: image.data,
+        })
+    }
+}
+
+impl From<Ros2RegionOfInterest> for RegionOfInterest {
+    fn from(roi: Ros2RegionOfInterest) -> Self {
+        RegionOfInterest {
+            x_offset: roi.x_offset,
+            y_offset: roi.y_offset,
+            height: roi.height,
+            width: roi.width,
+            do_rectify: roi.do_rectify,
+        }
+    }
+}
+
+impl From<RegionOfInterest> for Ros2RegionOfInterest {
+    fn from(roi: RegionOfInterest) -> Self {
+        Ros2RegionOfInterest {
+            x_offset: roi.x_offset,
+            y_offset: roi.y_offset,
+            height: roi.height,
+            width: roi.width,
+            do_rectify: roi.do_rectify,
+        }
+    }
+}
+
+/// Converts a ROS2 matrix (a `Vec<f64>` in `r2r`) into a fixed size array,
+/// failing if it does not have exactly `N` elements.
+fn into_array<const N: usize>(values: Vec<f64>, name: &str) -> Result<[f64; N], Box<dyn Error>> {
+    let len = values.len();
+    match <[f64; N]>::try_from(values) {
+        Ok(array) => Ok(array),
+        Err(_) => Err(format!("Invalid camera info matrix size [matrix: {}, expected: {}, actual: {}]", name, N, len))?
+    }
+}
+
+impl TryFrom<Ros2CameraInfo> for CameraInfo {
+    type Error = Box<dyn Error>;
+
+    fn try_from(info: Ros2CameraInfo) -> Result<Self, Self::Error> {
+        Ok(CameraInfo {
+            header: Header::try_from(info.header)?,
+            height: info.height,
+            width: info.width,
+            distortion_model: info.distortion_model,
+            D: info.d,
+            K: into_array(info.k, "K")?,
+            R: into_array(info.r, "R")?,
+            P: into_array(info.p, "P")?,
+            binning_x: info.binning_x,
+            binning_y: info.binning_y,
+            roi: info.roi.into(),
+        })
+    }
+}
+
+impl TryFrom<CameraInfo> for Ros2CameraInfo {
+    type Error = Box<dyn Error>;
+
+    fn try_from(info: CameraInfo) -> Result<Self, Self::Error> {
+        Ok(Ros2CameraInfo {
+            header: Ros2Header::try_from(info.header)?,
+            height: info.height,
+            width: info.width,
+            distortion_model: info.distortion_model,
+            d: info.D,
+            k: info.K.to_vec(),
+            r: info.R.to_vec(),
+            p: info.P.to_vec(),
+            binning_x: info.binning_x,
+            binning_y: info.binning_y,
+            roi: info.roi.into(),
+        })
+    }
+}
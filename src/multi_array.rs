@@ -0,0 +1,203 @@
+//! multi_array module converts between `CvImage`s and the `std_msgs` multi-array
+//! messages used to publish tensors, such as the output of inference nodes.
+//! Combined with `CvImage::as_cvmat` and `CvImage::from_cvmat`, they also convert
+//! to and from `opencv::core::Mat`.
+//!
+//! Two dimensional arrays are single channel images. Three dimensional arrays are
+//! multi-channel images with up to 4 channels, with the channel dimension last
+//! (`height x width x channel`) or first (`channel x height x width`). Following
+//! `std_msgs/MultiArrayLayout`, element `[i, j, k]` is read at
+//! `data_offset + dim[1].stride * i + dim[2].stride * j + k`, so padded arrays are
+//! supported. The arrays written by this module are dense and have no offset.
+
+use std::error::Error;
+
+use crate::cv_image::{
+    CvImage,
+    DataContainer,
+};
+use crate::msgs::std_msgs::{
+    Float32MultiArray,
+    Header,
+    MultiArrayDimension,
+    MultiArrayLayout,
+    UInt8MultiArray,
+};
+use crate::utils::image_encodings;
+
+/// Position of the channel dimension in three dimensional arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChannelOrder {
+    /// `height x width x channel`, the layout of images
+    #[default]
+    Last,
+    /// `channel x height x width`, the layout of most neural network tensors
+    First,
+}
+
+impl CvImage {
+    /// Constructs a `32FC*` `CvImage` from a `std_msgs::Float32MultiArray` message.
+    /// The header of the image is empty.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut scores = CvImage::from_float32_multiarray(&tensor, ChannelOrder::First).unwrap();
+    /// let mat = scores.as_cvmat().unwrap();
+    /// ```
+    ///
+    /// ## Arguments
+    /// * `array` - Two or three dimensional array
+    /// * `order` - Position of the channel dimension of three dimensional arrays
+    ///
+    /// ## Returns
+    /// * `CvImage` object
+    pub fn from_float32_multiarray(array: &Float32MultiArray, order: ChannelOrder) -> Result<CvImage, Box<dyn Error>> {
+        let (height, width, channels, data) = gather(&array.layout, &array.data, order)?;
+        let encoding = format!("32FC{}", channels);
+
+        Ok(CvImage::from_parts(Header::default(), height, width, &encoding, DataContainer::VecF32(data)))
+    }
+
+    /// Constructs a `8UC*` `CvImage` from a `std_msgs::UInt8MultiArray` message.
+    /// The header of the image is empty.
+    ///
+    /// ## Arguments
+    /// * `array` - Two or three dimensional array
+    /// * `order` - Position of the channel dimension of three dimensional arrays
+    ///
+    /// ## Returns
+    /// * `CvImage` object
+    pub fn from_uint8_multiarray(array: &UInt8MultiArray, order: ChannelOrder) -> Result<CvImage, Box<dyn Error>> {
+        let (height, width, channels, data) = gather(&array.layout, &array.data, order)?;
+        let encoding = format!("8UC{}", channels);
+
+        Ok(CvImage::from_parts(Header::default(), height, width, &encoding, DataContainer::VecU8(data)))
+    }
+
+    /// Converts a `32FC*` image into a dense `std_msgs::Float32MultiArray` message,
+    /// two dimensional for single channel images and three dimensional otherwise.
+    ///
+    /// ## Arguments
+    /// * `order` - Position of the channel dimension of three dimensional arrays
+    ///
+    /// ## Returns
+    /// * `std_msgs::Float32MultiArray` message
+    pub fn to_float32_multiarray(&self, order: ChannelOrder) -> Result<Float32MultiArray, Box<dyn Error>> {
+        let data = match (image_encodings::get_bit_depth(self.encoding()), self.as_container()) {
+            (32, DataContainer::VecF32(data)) => data,
+            _ => Err(format!("Unsupported encoding for Float32MultiArray [encoding: {}]", self.encoding()))?
+        };
+        let (layout, data) = scatter(data, self.height(), self.width(), image_encodings::get_num_channels(self.encoding()), order);
+
+        Ok(Float32MultiArray { layout, data })
+    }
+
+    /// Converts an 8-bit image into a dense `std_msgs::UInt8MultiArray` message,
+    /// two dimensional for single channel images and three dimensional otherwise.
+    ///
+    /// ## Arguments
+    /// * `order` - Position of the channel dimension of three dimensional arrays
+    ///
+    /// ## Returns
+    /// * `std_msgs::UInt8MultiArray` message
+    pub fn to_uint8_multiarray(&self, order: ChannelOrder) -> Result<UInt8MultiArray, Box<dyn Error>> {
+        let data = match (image_encodings::get_bit_depth(self.encoding()), self.as_container()) {
+            (8, DataContainer::VecU8(data)) => data,
+            _ => Err(format!("Unsupported encoding for UInt8MultiArray [encoding: {}]", self.encoding()))?
+        };
+        let (layout, data) = scatter(data, self.height(), self.width(), image_encodings::get_num_channels(self.encoding()), order);
+
+        Ok(UInt8MultiArray { layout, data })
+    }
+}
+
+/// `(height, width, channels, data)` of a dense `height x width x channel` buffer
+type Dense<T> = (usize, usize, usize, Vec<T>);
+
+/// Reads the elements of an array into a dense `height x width x channel` buffer
+fn gather<T: Copy>(layout: &MultiArrayLayout, data: &[T], order: ChannelOrder) -> Result<Dense<T>, Box<dyn Error>> {
+    let dims = &layout.dim;
+    if dims.len() != 2 && dims.len() != 3 {
+        Err(format!("Only 2 and 3 dimensional arrays are supported [dimensions: {}]", dims.len()))?
+    }
+
+    // Step in elements along each dimension; the last dimension is contiguous
+    let sizes: Vec<usize> = dims.iter().map(|dim| dim.size as usize).collect();
+    let steps: Vec<usize> = (0..dims.len())
+        .map(|k| match k + 1 < dims.len() {
+            true => dims[k + 1].stride as usize,
+            false => 1,
+        })
+        .collect();
+
+    // (size, step) of the height, width and channel dimensions
+    let (row, col, channel) = match (dims.len(), order) {
+        (2, _) => ((sizes[0], steps[0]), (sizes[1], steps[1]), (1, 0)),
+        (_, ChannelOrder::Last) => ((sizes[0], steps[0]), (sizes[1], steps[1]), (sizes[2], steps[2])),
+        (_, ChannelOrder::First) => ((sizes[1], steps[1]), (sizes[2], steps[2]), (sizes[0], steps[0])),
+    };
+    if !(1..=4).contains(&channel.0) {
+        Err(format!("Only 1 to 4 channels are supported [channels: {}]", channel.0))?
+    }
+
+    let offset = layout.data_offset as usize;
+    if sizes.iter().all(|size| *size > 0) {
+        let last = sizes.iter()
+            .zip(&steps)
+            .fold(offset, |last, (size, step)| last.saturating_add((size - 1).saturating_mul(*step)));
+        if last >= data.len() {
+            Err(format!(
+                "Array layout exceeds the data [last index: {}, data length: {}]",
+                last, data.len()
+            ))?
+        }
+    }
+
+    let mut dense = Vec::with_capacity(row.0 * col.0 * channel.0);
+    for y in 0..row.0 {
+        for x in 0..col.0 {
+            for c in 0..channel.0 {
+                dense.push(data[offset + y * row.1 + x * col.1 + c * channel.1]);
+            }
+        }
+    }
+
+    Ok((row.0, col.0, channel.0, dense))
+}
+
+/// Writes a dense `height x width x channel` buffer into an array with the given channel order
+fn scatter<T: Copy>(data: &[T], height: usize, width: usize, channels: usize, order: ChannelOrder) -> (MultiArrayLayout, Vec<T>) {
+    let dimension = |label: &str, size: usize, stride: usize| MultiArrayDimension {
+        label: label.to_string(),
+        size: size as u32,
+        stride: stride as u32,
+    };
+
+    let (dim, data) = match (channels, order) {
+        (1, _) | (_, ChannelOrder::Last) => {
+            let mut dim = vec![
+                dimension("height", height, height * width * channels),
+                dimension("width", width, width * channels),
+            ];
+            if channels > 1 {
+                dim.push(dimension("channel", channels, channels));
+            }
+
+            (dim, data.to_vec())
+        },
+        (_, ChannelOrder::First) => {
+            let dim = vec![
+                dimension("channel", channels, channels * height * width),
+                dimension("height", height, height * width),
+                dimension("width", width, width),
+            ];
+            let planar = (0..channels)
+                .flat_map(|c| data.iter().skip(c).step_by(channels).copied())
+                .collect();
+
+            (dim, planar)
+        },
+    };
+
+    (MultiArrayLayout { dim, data_offset: 0 }, data)
+}
@@ -13,7 +13,7 @@ use byteorder::{
 /// ## Arguments
 /// * `data` - The u8 array to convert.
 /// * `big_endian` - If true, the u8 array is in big endian byte order.
-///                 If false, the u8 array is in little endian byte order.
+///   If false, the u8 array is in little endian byte order.
 /// 
 /// ## Returns
 /// A u16 array.
@@ -40,7 +40,7 @@ pub fn from_u8_to_u16(data: &[u8], big_endian: bool) -> Vec<u16> {
 /// ## Arguments
 /// * `data` - The u16 array to convert.
 /// * `big_endian` - If true, the u8 array will be in big endian byte order.
-///                If false, the u8 array will be in little endian byte order.
+///   If false, the u8 array will be in little endian byte order.
 /// 
 /// ## Returns
 /// A u8 array.
@@ -60,6 +60,59 @@ pub fn from_u16_to_u8(data: &[u16], big_endian: bool) -> Vec<u8> {
     data8
 }
 
+/// Takes a u8 array and constructs a f32 array by converting
+/// four u8 values into one f32 value.
+/// 
+/// ## Arguments
+/// * `data` - The u8 array to convert.
+/// * `big_endian` - If true, the u8 array is in big endian byte order.
+///   If false, the u8 array is in little endian byte order.
+/// 
+/// ## Returns
+/// A f32 array.
+pub fn from_u8_to_f32(data: &[u8], big_endian: bool) -> Vec<f32> {
+    let mut data32 = Vec::with_capacity(data.len() / 4);
+    let mut cursor = Cursor::new(data);
+
+    if big_endian {
+        while let Ok(value) = cursor.read_f32::<BigEndian>() {
+            data32.push(value);
+        }
+    } else {
+        while let Ok(value) = cursor.read_f32::<LittleEndian>() {
+            data32.push(value);
+        }
+    }
+
+    data32
+}
+
+/// Takes a f32 array and constructs a u8 array by converting
+/// one f32 value into four u8 values.
+/// 
+/// ## Arguments
+/// * `data` - The f32 array to convert.
+/// * `big_endian` - If true, the u8 array will be in big endian byte order.
+///   If false, the u8 array will be in little endian byte order.
+/// 
+/// ## Returns
+/// A u8 array.
+pub fn from_f32_to_u8(data: &[f32], big_endian: bool) -> Vec<u8> {
+    let mut data8 = Vec::with_capacity(data.len() * 4);
+
+    if big_endian {
+        for value in data {
+            data8.write_f32::<BigEndian>(*value).unwrap();
+        }
+    } else {
+        for value in data {
+            data8.write_f32::<LittleEndian>(*value).unwrap();
+        }
+    }
+
+    data8
+}
+
 /// Takes a u8 array in big endian byte order and converts it to little endian byte order.
 /// 
 /// ## Arguments
@@ -21,8 +21,20 @@
 //! * `bayer_grbg16` - 16-bit Bayer image
 //! * `yuv422` - 8-bit 2 channel image
 //! * `yuv422_yuy2` - 8-bit 2 channel image
+//! * `8UC1` - 8-bit 1 channel image
+//! * `8UC2` - 8-bit 2 channel image
+//! * `8UC3` - 8-bit 3 channel image
+//! * `8UC4` - 8-bit 4 channel image
+//! * `16UC1` - 16-bit 1 channel image
+//! * `16UC2` - 16-bit 2 channel image
+//! * `16UC3` - 16-bit 3 channel image
+//! * `16UC4` - 16-bit 4 channel image
+//! * `32FC1` - 32-bit 1 channel floating point image
+//! * `32FC2` - 32-bit 2 channel floating point image
+//! * `32FC3` - 32-bit 3 channel floating point image
+//! * `32FC4` - 32-bit 4 channel floating point image
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Encoding {
     Gray,
     Rgb,
@@ -67,6 +79,18 @@ pub fn get_num_channels(encoding: &str) -> usize {
         "bayer_grbg16" => 1,
         "yuv422" => 2,
         "yuv422_yuy2" => 2,
+        "8UC1" => 1,
+        "8UC2" => 2,
+        "8UC3" => 3,
+        "8UC4" => 4,
+        "16UC1" => 1,
+        "16UC2" => 2,
+        "16UC3" => 3,
+        "16UC4" => 4,
+        "32FC1" => 1,
+        "32FC2" => 2,
+        "32FC3" => 3,
+        "32FC4" => 4,
         _ => 0
     }
 }
@@ -75,7 +99,7 @@ pub fn get_num_channels(encoding: &str) -> usize {
 /// 
 /// ## Arguments
 /// * `encoding` - The encoding to get the bit depth for (e.g. "rgb8")
-///                Note: The bit depth is the number of bits per channel
+///   Note: The bit depth is the number of bits per channel
 /// 
 /// ## Returns
 /// The bit depth for the given encoding
@@ -101,6 +125,18 @@ pub fn get_bit_depth(encoding: &str) -> u8 {
         "bayer_grbg16" => 16,
         "yuv422" => 8,
         "yuv422_yuy2" => 8,
+        "8UC1" => 8,
+        "8UC2" => 8,
+        "8UC3" => 8,
+        "8UC4" => 8,
+        "16UC1" => 16,
+        "16UC2" => 16,
+        "16UC3" => 16,
+        "16UC4" => 16,
+        "32FC1" => 32,
+        "32FC2" => 32,
+        "32FC3" => 32,
+        "32FC4" => 32,
         _ => 0
     }
 }
@@ -122,10 +158,10 @@ pub fn get_scaling_factor(src_encoding: &str, dst_encoding: &str) -> f64 {
         return 0.0;
     }
 
-    f64::powi(2.0, dst_depth - src_depth) as f64
+    f64::powi(2.0, dst_depth - src_depth)
 }
 
-/// Returns the OpenCV type for the given encoding
+/// Returns the OpenCV type for the given encoding. Only available with the `opencv` feature.
 /// 
 /// ## Arguments
 /// * `encoding` - The encoding to get the OpenCV type for (e.g. "rgb8")
@@ -133,6 +169,7 @@ pub fn get_scaling_factor(src_encoding: &str, dst_encoding: &str) -> f64 {
 /// ## Returns
 /// The OpenCV type for the given encoding or an error if the encoding is invalid
 /// (eg. opencv::core::CV_8UC3)
+#[cfg(feature = "opencv")]
 pub fn from_encstr_to_cvtype(encoding: &str) -> Result<i32, String> {
     match encoding {
         "mono8" => Ok(opencv::core::CV_8UC1),
@@ -155,6 +192,18 @@ pub fn from_encstr_to_cvtype(encoding: &str) -> Result<i32, String> {
         "bayer_grbg16" => Ok(opencv::core::CV_16UC1),
         "yuv422" => Ok(opencv::core::CV_8UC2),
         "yuv422_yuy2" => Ok(opencv::core::CV_8UC2),
+        "8UC1" => Ok(opencv::core::CV_8UC1),
+        "8UC2" => Ok(opencv::core::CV_8UC2),
+        "8UC3" => Ok(opencv::core::CV_8UC3),
+        "8UC4" => Ok(opencv::core::CV_8UC4),
+        "16UC1" => Ok(opencv::core::CV_16UC1),
+        "16UC2" => Ok(opencv::core::CV_16UC2),
+        "16UC3" => Ok(opencv::core::CV_16UC3),
+        "16UC4" => Ok(opencv::core::CV_16UC4),
+        "32FC1" => Ok(opencv::core::CV_32FC1),
+        "32FC2" => Ok(opencv::core::CV_32FC2),
+        "32FC3" => Ok(opencv::core::CV_32FC3),
+        "32FC4" => Ok(opencv::core::CV_32FC4),
         _ => Err(format!("Unsupported encoding type: {}", encoding))
     }
 }
@@ -197,7 +246,7 @@ pub fn from_encstr_to_cvenc(encoding: &str) -> Result<Encoding, String> {
 /// ## Arguments
 /// * `cvenc` - The OpenCV encoding to get the encoding for
 /// * `cvdepth` - The OpenCV bit depth to get the encoding for
-///          (0 = unsigned 8-bit, 1 = signed 8-bit, 2 = unsigned 16-bit, 3 = signed 16-bit)
+///   (0 = unsigned 8-bit, 1 = signed 8-bit, 2 = unsigned 16-bit, 3 = signed 16-bit)
 pub fn from_cvenc_to_encstr(cvenc: Encoding, cvdepth: i32) -> Result<String, String> {
     match (cvenc, cvdepth) {
         (Encoding::Gray, 0) => Ok("mono8".to_string()),
@@ -238,12 +287,13 @@ pub fn from_cvenc_to_encstr(cvenc: Encoding, cvdepth: i32) -> Result<String, Str
         (Encoding::BayerGRBG, 3) => Ok("bayer_grbg16".to_string()),
         (Encoding::Yuv422, 0) => Ok("yuv422".to_string()),
         (Encoding::Yuv422yuy2, 0) => Ok("yuv422_yuy2".to_string()),
-        _ => Err(format!("Unsupported encoding type"))
+        _ => Err("Unsupported encoding type".to_string())
 
     }
 }
 
-/// Returns the conversion code for going from one color space to another
+/// Returns the conversion code for going from one color space to another.
+/// Only available with the `opencv` feature.
 /// 
 /// ## Arguments
 /// * `src_encoding` - The source encoding
@@ -251,6 +301,7 @@ pub fn from_cvenc_to_encstr(cvenc: Encoding, cvdepth: i32) -> Result<String, Str
 /// 
 /// ## Returns
 /// * `Ok(i32)` - The conversion code (eg. opencv::imgproc::COLOR_RGB2GRAY)
+#[cfg(feature = "opencv")]
 pub fn get_conversion_code(src_encoding: Encoding, dst_encoding: Encoding) -> Result<i32, String>{
     match (&src_encoding, &dst_encoding) {
         (Encoding::Gray, Encoding::Rgb) => Ok(opencv::imgproc::COLOR_GRAY2RGB),
@@ -287,9 +338,9 @@ pub fn get_conversion_code(src_encoding: Encoding, dst_encoding: Encoding) -> Re
         (Encoding::BayerGRBG, Encoding::Bgr) => Ok(opencv::imgproc::COLOR_BayerGB2BGR),
         (Encoding::Yuv422, Encoding::Gray) => Ok(opencv::imgproc::COLOR_YUV2GRAY_UYVY),
         (Encoding::Yuv422, Encoding::Rgb) => Ok(opencv::imgproc::COLOR_YUV2RGB_UYVY),
-        (Encoding::Yuv422, Encoding::Bgr) => Ok(opencv::imgproc::COLOR_YUV2BGRA_UYVY),
+        (Encoding::Yuv422, Encoding::Bgr) => Ok(opencv::imgproc::COLOR_YUV2BGR_UYVY),
         (Encoding::Yuv422, Encoding::Rgba) => Ok(opencv::imgproc::COLOR_YUV2RGBA_UYVY),
-        (Encoding::Yuv422, Encoding::Bgra) => Ok(opencv::imgproc::COLOR_YUV2BGR_UYVY),
+        (Encoding::Yuv422, Encoding::Bgra) => Ok(opencv::imgproc::COLOR_YUV2BGRA_UYVY),
         (Encoding::Yuv422yuy2, Encoding::Gray) => Ok(opencv::imgproc::COLOR_YUV2GRAY_YUY2),
         (Encoding::Yuv422yuy2, Encoding::Rgb) => Ok(opencv::imgproc::COLOR_YUV2RGB_YUY2),
         (Encoding::Yuv422yuy2, Encoding::Bgr) => Ok(opencv::imgproc::COLOR_YUV2BGR_YUY2),
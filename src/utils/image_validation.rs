@@ -0,0 +1,80 @@
+//! This module checks that the fields of image messages are consistent before
+//! their data is used. `CvImage::from_imgmsg` runs these checks, so a malformed
+//! message is rejected with an error instead of letting `CvImage::as_cvmat` read
+//! past the end of the buffer.
+//!
+//! ## Checks
+//! * The encoding is one of the encodings in the `image_encodings` module
+//! * `is_bigendian` is 0 or 1
+//! * `step` is at least `width * bytes per pixel`
+//! * The data length is exactly `step * height`
+//! * `yuv422` images have an even width, as two pixels share their chroma
+use std::error::Error;
+
+use crate::image_message::ImageMessage;
+use super::image_encodings;
+
+/// Returns the number of bytes of one pixel of the encoding, or an error for
+/// unknown encodings.
+///
+/// ## Arguments
+/// * `encoding` - The encoding of the image (e.g. "rgb8")
+///
+/// ## Returns
+/// The number of bytes per pixel
+pub fn bytes_per_pixel(encoding: &str) -> Result<usize, Box<dyn Error>> {
+    let channels = image_encodings::get_num_channels(encoding);
+    let bit_depth = image_encodings::get_bit_depth(encoding) as usize;
+
+    match channels == 0 || bit_depth == 0 {
+        true => Err(format!("Unknown encoding [encoding: {}]", encoding))?,
+        false => Ok(channels * bit_depth / 8),
+    }
+}
+
+/// Checks the fields of an image message.
+///
+/// # Example
+/// ```ignore
+/// if let Err(e) = validate_imgmsg(&image) {
+///     rosrust::ros_warn!("Dropping malformed image: {}", e);
+/// }
+/// ```
+///
+/// ## Arguments
+/// * `image` - `sensor_msgs::Image` message implementing `ImageMessage`
+///
+/// ## Returns
+/// * `Ok(())` if the message is consistent, an error describing the first failed check otherwise
+pub fn validate_imgmsg<T: ImageMessage>(image: &T) -> Result<(), Box<dyn Error>> {
+    let encoding = image.encoding();
+    let bytes_per_pixel = bytes_per_pixel(encoding)?;
+
+    let is_bigendian = image.is_bigendian();
+    if is_bigendian > 1 {
+        Err(format!("Unsupported endianness [is_bigendian: {}]", is_bigendian))?
+    }
+
+    let (height, width, step) = (image.height() as u64, image.width() as u64, image.step() as u64);
+    if encoding.starts_with("yuv422") && width % 2 != 0 {
+        Err(format!("YUV 4:2:2 images must have an even width [encoding: {}, width: {}]", encoding, width))?
+    }
+
+    let row_len = width * bytes_per_pixel as u64;
+    if step < row_len {
+        Err(format!(
+            "Step is smaller than the row length [step: {}, width: {}, bytes per pixel: {}]",
+            step, width, bytes_per_pixel
+        ))?
+    }
+
+    let data_len = image.data().len() as u64;
+    if data_len != step * height {
+        Err(format!(
+            "Data length does not match step * height [data length: {}, step: {}, height: {}]",
+            data_len, step, height
+        ))?
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 //! Utilities for image processing
 
 pub mod image_encodings;
-pub mod image_byteorder_ops;
\ No newline at end of file
+pub mod image_byteorder_ops;
+pub mod image_validation;
\ No newline at end of file
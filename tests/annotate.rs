@@ -0,0 +1,129 @@
+#![cfg(feature = "opencv")]
+
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::{
+        Time,
+        std_msgs::Header,
+        sensor_msgs::Image,
+    },
+};
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 80;
+
+fn header() -> Header {
+    Header {
+        seq: 7,
+        stamp: Time { sec: 1234, nsec: 5 },
+        frame_id: "camera".to_string(),
+    }
+}
+
+fn mono8_image(width: usize, height: usize, value: u8) -> CvImage {
+    CvImage::from_parts(header(), height, width, "mono8", DataContainer::VecU8(vec![value; width * height]))
+}
+
+fn bgr(image: &CvImage) -> &[u8] {
+    match image.as_container() {
+        DataContainer::VecU8(data) => data,
+        container => panic!("unexpected container {:?}", container),
+    }
+}
+
+/// Returns the rightmost column of the rows with a bright (text) pixel, if any
+fn text_extent(image: &CvImage, rows: std::ops::Range<usize>) -> Option<usize> {
+    let data = bgr(image);
+    rows.flat_map(|y| (0..image.width()).map(move |x| (x, y)))
+        .filter(|(x, y)| data[(y * image.width() + x) * 3] > 128)
+        .map(|(x, _)| x)
+        .max()
+}
+
+#[test]
+fn integer_images_keep_their_values() {
+    let annotated = mono8_image(2, 2, 100).annotate().unwrap().into_cvimage();
+    assert_eq!(annotated.encoding(), "bgr8");
+    assert_eq!(bgr(&annotated), &[100; 12]);
+
+    let data = [0u16, 32768, 65535, 65535].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let mut mono16 = CvImage::from_parts(header(), 2, 2, "mono16", DataContainer::VecU8(data));
+    let annotated = mono16.annotate().unwrap().into_cvimage();
+    assert_eq!(bgr(&annotated), &[0, 0, 0, 128, 128, 128, 255, 255, 255, 255, 255, 255]);
+}
+
+#[test]
+fn mask_blends_color() {
+    let mask = CvImage::from_parts(Header::default(), 2, 2, "mono8", DataContainer::VecU8(vec![0, 1, 255, 0]));
+
+    let mut annotator = mono8_image(2, 2, 100).annotate().unwrap();
+    annotator.mask(&mask, [200, 0, 50], 0.5).unwrap();
+    let annotated = annotator.into_cvimage();
+
+    assert_eq!(bgr(&annotated), &[
+        100, 100, 100,
+        150, 50, 75,
+        150, 50, 75,
+        100, 100, 100,
+    ]);
+}
+
+#[test]
+fn invalid_masks_fail() {
+    let mut annotator = mono8_image(2, 2, 100).annotate().unwrap();
+
+    let small_mask = CvImage::from_parts(Header::default(), 1, 2, "mono8", DataContainer::VecU8(vec![1, 1]));
+    assert!(annotator.mask(&small_mask, [0, 0, 255], 0.5).is_err());
+
+    let mask = CvImage::from_parts(Header::default(), 2, 2, "mono8", DataContainer::VecU8(vec![1; 4]));
+    assert!(annotator.mask(&mask, [0, 0, 255], 1.5).is_err());
+
+    let rgb_mask = CvImage::from_parts(Header::default(), 2, 2, "rgb8", DataContainer::VecU8(vec![1; 12]));
+    assert!(annotator.mask(&rgb_mask, [0, 0, 255], 0.5).is_err());
+
+    // The failed calls do not modify the image
+    assert_eq!(bgr(&annotator.into_cvimage()), &[100; 12]);
+}
+
+#[test]
+fn annotations_keep_the_header() {
+    let mut annotator = mono8_image(WIDTH, HEIGHT, 128).annotate().unwrap();
+    annotator
+        .bounding_box((10, 30, 40, 30), Some("label"), [0, 255, 0]).unwrap()
+        .polyline(&[(0, 0), (20, 20), (40, 0)], false, [255, 0, 0]).unwrap()
+        .circle((100, 50), 3, [0, 0, 255]).unwrap()
+        .text((150, 60), "text", [255, 255, 255]).unwrap();
+
+    assert_eq!(*annotator.clone().into_cvimage().header(), header());
+
+    let message: Image = annotator.into_imgmsg().unwrap();
+    assert_eq!(message.header, header());
+    assert_eq!(message.encoding, "bgr8");
+    assert_eq!((message.width, message.height, message.step), (WIDTH as u32, HEIGHT as u32, WIDTH as u32 * 3));
+}
+
+#[test]
+fn stamp_banners_are_stacked() {
+    let mut annotator = mono8_image(WIDTH, HEIGHT, 128).annotate().unwrap();
+    annotator.stamp_banner(None).unwrap();
+    let stamp_only = annotator.clone().into_cvimage();
+    annotator.stamp_banner(Some(29.8)).unwrap();
+    let with_fps = annotator.into_cvimage();
+
+    // The first banner is a black strip with white text across the top of the image
+    let data = bgr(&stamp_only);
+    let banner_height = (0..HEIGHT).find(|y| data[y * WIDTH * 3] != 0).unwrap();
+    assert!(banner_height > 0 && 2 * banner_height < HEIGHT);
+    assert!(data[..WIDTH * 3].iter().all(|value| *value == 0));
+    assert!(data[banner_height * WIDTH * 3..].iter().all(|value| *value == 128));
+    let stamp_extent = text_extent(&stamp_only, 0..banner_height).unwrap();
+
+    // The FPS banner is drawn below the first one and is longer
+    let data = bgr(&with_fps);
+    assert_eq!(bgr(&stamp_only)[..banner_height * WIDTH * 3], data[..banner_height * WIDTH * 3]);
+    assert_eq!(data[banner_height * WIDTH * 3], 0);
+    let fps_extent = text_extent(&with_fps, banner_height..2 * banner_height).unwrap();
+    assert!(fps_extent > stamp_extent);
+    assert!(data[(HEIGHT - 1) * WIDTH * 3..].iter().all(|value| *value == 128));
+}
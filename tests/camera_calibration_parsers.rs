@@ -0,0 +1,100 @@
+use cv_bridge::{
+    camera_calibration_parsers::{
+        self,
+        CalibrationFormat,
+    },
+    msgs::sensor_msgs::CameraInfo,
+};
+
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: 640,
+        height: 480,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![-0.337, 0.117, 1e-7, -0.0002, 0.0],
+        K: [430.15, 0.0, 311.71, 0.0, 430.53, 221.07, 0.0, 0.0, 1.0],
+        R: [0.999, 0.01, -0.02, -0.01, 0.999, 0.003, 0.02, -0.003, 0.999],
+        P: [357.43, 0.0, 306.91, -42.1, 0.0, 362.73, 222.09, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn ini_round_trip() {
+    let buffer = camera_calibration_parsers::format_calibration("narrow_stereo", &camera_info(), CalibrationFormat::Ini).unwrap();
+    let (camera_name, parsed) = camera_calibration_parsers::parse_calibration(&buffer, CalibrationFormat::Ini).unwrap();
+
+    assert_eq!(camera_name, "narrow_stereo");
+    assert_eq!(parsed, camera_info());
+}
+
+#[test]
+fn ini_malformed_matrix() {
+    let buffer = "[image]\nwidth\n640\nheight\n480\n[cam]\ncamera matrix\n1 0 0\n0 1 0\n\
+                  distortion\n0 0 0 0 0\nrectification\n1 0 0\n0 1 0\n0 0 1\n\
+                  projection\n1 0 0 0\n0 1 0 0\n0 0 1 0\n";
+
+    let error = camera_calibration_parsers::ini::parse_calibration(buffer).unwrap_err();
+    assert!(error.to_string().contains("camera matrix"), "{}", error);
+}
+
+#[test]
+fn unsupported_extension() {
+    assert!(CalibrationFormat::from_path("camera.txt".as_ref()).is_err());
+    assert_eq!(CalibrationFormat::from_path("camera.YML".as_ref()).unwrap(), CalibrationFormat::Yaml);
+}
+
+#[test]
+fn write_and_read_file() {
+    let path = std::env::temp_dir().join(format!("cv_bridge_calibration_{}", std::process::id())).join("camera.ini");
+
+    camera_calibration_parsers::write_calibration(&path, "camera", &camera_info()).unwrap();
+    let (camera_name, parsed) = camera_calibration_parsers::read_calibration(&path).unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+
+    assert_eq!(camera_name, "camera");
+    assert_eq!(parsed, camera_info());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_round_trip() {
+    let buffer = camera_calibration_parsers::format_calibration("yes", &camera_info(), CalibrationFormat::Yaml).unwrap();
+    let (camera_name, parsed) = camera_calibration_parsers::parse_calibration(&buffer, CalibrationFormat::Yaml).unwrap();
+
+    assert_eq!(camera_name, "yes");
+    assert_eq!(parsed, camera_info());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_from_camera_calibration() {
+    // Layout written by the camera_calibration node
+    let buffer = "image_width: 640\n\
+                  image_height: 480\n\
+                  camera_name: narrow_stereo\n\
+                  camera_matrix:\n  rows: 3\n  cols: 3\n  data: [430.15, 0., 311.71,\n         0., 430.53, 221.07, 0., 0., 1.]\n\
+                  distortion_coefficients:\n  rows: 1\n  cols: 8\n  data: [0, 0, 0, 0, 0, 0, 0, 0]\n\
+                  rectification_matrix:\n  rows: 3\n  cols: 3\n  data: [1, 0, 0, 0, 1, 0, 0, 0, 1]\n\
+                  projection_matrix:\n  rows: 3\n  cols: 4\n  data: [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0]\n";
+
+    let (camera_name, parsed) = camera_calibration_parsers::yaml::parse_calibration(buffer).unwrap();
+
+    assert_eq!(camera_name, "narrow_stereo");
+    assert_eq!(parsed.distortion_model, "rational_polynomial");
+    assert_eq!(parsed.K[2], 311.71);
+    assert_eq!(parsed.D.len(), 8);
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn yaml_malformed_matrix() {
+    let buffer = "image_width: 640\nimage_height: 480\n\
+                  camera_matrix:\n  rows: 3\n  cols: 3\n  data: [1, 0, 0, 0, 1, 0, 0, 0]\n\
+                  distortion_coefficients:\n  rows: 1\n  cols: 0\n  data: []\n\
+                  rectification_matrix:\n  rows: 3\n  cols: 3\n  data: [1, 0, 0, 0, 1, 0, 0, 0, 1]\n\
+                  projection_matrix:\n  rows: 4\n  cols: 3\n  data: [1, 0, 0, 0, 0, 1, 0, 0, 0, 0, 1, 0]\n";
+
+    let error = camera_calibration_parsers::yaml::parse_calibration(buffer).unwrap_err();
+    assert!(error.to_string().contains("camera_matrix"), "{}", error);
+}
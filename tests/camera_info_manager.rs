@@ -0,0 +1,132 @@
+use std::path::PathBuf;
+
+use cv_bridge::{
+    CvImage,
+    camera_info_manager::{
+        CameraInfoManager,
+        resolve_calibration_url,
+    },
+    cv_image::DataContainer,
+    msgs::{
+        Time,
+        std_msgs::Header,
+        sensor_msgs::{
+            CameraInfo,
+            SetCameraInfoReq,
+        },
+    },
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cv_bridge_{}_{}", name, std::process::id()))
+}
+
+fn calibration() -> CameraInfo {
+    CameraInfo {
+        width: 640,
+        height: 480,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![0.1, -0.2, 0.0, 0.0, 0.05],
+        K: [525.0, 0.0, 319.5, 0.0, 520.0, 239.5, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [510.0, 0.0, 318.0, 0.0, 0.0, 505.0, 241.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn missing_file_is_uncalibrated() {
+    let dir = temp_dir("missing");
+    let url = format!("file://{}/${{NAME}}.ini", dir.display());
+
+    let manager = CameraInfoManager::new("camera", &url).unwrap();
+
+    assert!(!manager.is_calibrated());
+    assert_eq!(*manager.camera_info(), CameraInfo::default());
+}
+
+#[test]
+fn set_camera_info_persists() {
+    let dir = temp_dir("persist");
+    let url = format!("file://{}/${{NAME}}.ini", dir.display());
+
+    let mut manager = CameraInfoManager::new("camera", &url).unwrap();
+    let response = manager.handle_set_camera_info(SetCameraInfoReq { camera_info: calibration() });
+    assert!(response.success, "{}", response.status_message);
+
+    let written = dir.join("camera.ini").is_file();
+    let reloaded = CameraInfoManager::new("camera", &url).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(written);
+    assert!(reloaded.is_calibrated());
+    assert_eq!(*reloaded.camera_info(), calibration());
+}
+
+#[test]
+fn set_camera_info_reports_failure() {
+    let mut manager = CameraInfoManager::new("camera", "file:///proc/cv_bridge/camera.ini").unwrap();
+    let response = manager.handle_set_camera_info(SetCameraInfoReq { camera_info: calibration() });
+
+    assert!(!response.success);
+    assert!(!response.status_message.is_empty());
+    assert!(manager.is_calibrated());
+}
+
+#[test]
+fn package_url() {
+    let dir = temp_dir("package");
+    let package = dir.join("src").join("my_camera");
+    std::fs::create_dir_all(package.join("calibration")).unwrap();
+    std::fs::write(package.join("package.xml"), "<package/>").unwrap();
+
+    let resolve = |url: &str| resolve_calibration_url(url, "camera", "/home/user/.ros", dir.as_os_str());
+    let resolved = resolve("package://my_camera/calibration/${NAME}.ini");
+    let missing = resolve("package://missing_package/camera.ini");
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(resolved.unwrap(), package.join("calibration").join("camera.ini"));
+    assert!(missing.is_err());
+    assert!(resolve("package:///camera.ini").is_err());
+}
+
+#[test]
+fn resolve_urls() {
+    let resolve = |url: &str| resolve_calibration_url(url, "camera", "/home/user/.ros", "".as_ref());
+
+    assert_eq!(resolve("file:///tmp/${NAME}.ini").unwrap(), PathBuf::from("/tmp/camera.ini"));
+    assert_eq!(resolve("file://${ROS_HOME}/calib.yaml").unwrap(), PathBuf::from("/home/user/.ros/calib.yaml"));
+    assert!(resolve("http://example.com/camera.ini").is_err());
+
+    // The default file format needs to be readable with the enabled features
+    let default = match cfg!(feature = "yaml") {
+        true => "/home/user/.ros/camera_info/camera.yaml",
+        false => "/home/user/.ros/camera_info/camera.ini",
+    };
+    assert_eq!(resolve("").unwrap(), PathBuf::from(default));
+}
+
+#[test]
+fn unsupported_url_is_invalid() {
+    let manager = CameraInfoManager::new("camera", "file:///nonexistent/camera.ini").unwrap();
+
+    assert!(!manager.validate_url("http://example.com/camera.ini"));
+}
+
+#[test]
+fn invalid_camera_name() {
+    assert!(CameraInfoManager::new("my camera", "file:///tmp/camera.ini").is_err());
+}
+
+#[test]
+fn stamps_camera_info() {
+    let manager = CameraInfoManager::new("camera", "file:///nonexistent/camera.ini").unwrap();
+    let header = Header {
+        seq: 7,
+        stamp: Time { sec: 10, nsec: 20 },
+        frame_id: "camera_optical".to_string(),
+    };
+    let image = CvImage::from_parts(header.clone(), 1, 1, "mono8", DataContainer::VecU8(vec![0]));
+
+    assert_eq!(manager.camera_info_for_image(&image).header, header);
+}
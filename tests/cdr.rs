@@ -0,0 +1,147 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::cdr::CdrMessage,
+    msgs::sensor_msgs::{
+        Image,
+        CompressedImage,
+        CameraInfo,
+    },
+};
+
+/// `sensor_msgs/Image` with stamp 1.2, frame_id "cam" and a 1x2 mono8 image
+const IMAGE_LE: [u8; 50] = [
+    0x00, 0x01, 0x00, 0x00,                         // encapsulation (CDR_LE)
+    0x01, 0x00, 0x00, 0x00,                         // stamp.sec
+    0x02, 0x00, 0x00, 0x00,                         // stamp.nanosec
+    0x04, 0x00, 0x00, 0x00, b'c', b'a', b'm', 0x00, // frame_id
+    0x01, 0x00, 0x00, 0x00,                         // height
+    0x02, 0x00, 0x00, 0x00,                         // width
+    0x06, 0x00, 0x00, 0x00,                         // encoding length
+    b'm', b'o', b'n', b'o', b'8', 0x00,             // encoding
+    0x00,                                           // is_bigendian
+    0x00,                                           // padding
+    0x02, 0x00, 0x00, 0x00,                         // step
+    0x02, 0x00, 0x00, 0x00, 0x0a, 0x14,             // data
+];
+
+/// Same message as `IMAGE_LE` serialized as big-endian CDR
+const IMAGE_BE: [u8; 50] = [
+    0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x02,
+    0x00, 0x00, 0x00, 0x04, b'c', b'a', b'm', 0x00,
+    0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x02,
+    0x00, 0x00, 0x00, 0x06,
+    b'm', b'o', b'n', b'o', b'8', 0x00,
+    0x00,
+    0x00,
+    0x00, 0x00, 0x00, 0x02,
+    0x00, 0x00, 0x00, 0x02, 0x0a, 0x14,
+];
+
+fn check_image_fixture(image: &Image) {
+    assert_eq!(image.header.stamp.sec, 1);
+    assert_eq!(image.header.stamp.nsec, 2);
+    assert_eq!(image.header.frame_id, "cam");
+    assert_eq!(image.height, 1);
+    assert_eq!(image.width, 2);
+    assert_eq!(image.encoding, "mono8");
+    assert_eq!(image.is_bigendian, 0);
+    assert_eq!(image.step, 2);
+    assert_eq!(image.data, vec![10, 20]);
+}
+
+#[test]
+fn image_from_little_endian_fixture() {
+    check_image_fixture(&Image::from_cdr(&IMAGE_LE).unwrap());
+}
+
+#[test]
+fn image_from_big_endian_fixture() {
+    check_image_fixture(&Image::from_cdr(&IMAGE_BE).unwrap());
+}
+
+#[test]
+fn image_to_cdr_matches_fixture() {
+    let image = Image::from_cdr(&IMAGE_LE).unwrap();
+    assert_eq!(image.to_cdr().unwrap(), IMAGE_LE.to_vec());
+}
+
+#[test]
+fn cvimage_from_cdr_fixture() {
+    let cv_image = CvImage::from_cdr(&IMAGE_LE).unwrap();
+    assert_eq!(cv_image.header().frame_id, "cam");
+    assert_eq!(cv_image.encoding(), "mono8");
+    match cv_image.as_container() {
+        DataContainer::VecU8(data) => assert_eq!(data, &vec![10, 20]),
+        container => panic!("unexpected container {:?}", container),
+    }
+
+    assert_eq!(cv_image.into_cdr(0).unwrap(), IMAGE_LE.to_vec());
+}
+
+#[test]
+fn truncated_image_fails() {
+    for len in 0..IMAGE_LE.len() {
+        assert!(Image::from_cdr(&IMAGE_LE[..len]).is_err(), "length {} should fail", len);
+    }
+}
+
+#[test]
+fn unsupported_representation_fails() {
+    let mut bytes = IMAGE_LE;
+    bytes[1] = 0x02;
+    assert!(Image::from_cdr(&bytes).is_err());
+}
+
+#[test]
+fn compressed_image_round_trip() {
+    let mut image = CompressedImage::default();
+    image.header.frame_id = "camera_optical".to_string();
+    image.format = "jpeg".to_string();
+    image.data = vec![0xff, 0xd8, 0xff, 0xd9];
+
+    assert_eq!(CompressedImage::from_cdr(&image.to_cdr().unwrap()).unwrap(), image);
+}
+
+#[test]
+fn camera_info_round_trip() {
+    let mut info = CameraInfo::default();
+    info.header.stamp.sec = 10;
+    info.header.stamp.nsec = 500;
+    info.header.frame_id = "cam".to_string();
+    info.height = 480;
+    info.width = 640;
+    info.distortion_model = "plumb_bob".to_string();
+    info.D = vec![0.1, -0.2, 0.001, 0.002, 0.0];
+    info.K = [500.0, 0.0, 320.0, 0.0, 500.0, 240.0, 0.0, 0.0, 1.0];
+    info.R = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0];
+    info.P = [500.0, 0.0, 320.0, 0.0, 0.0, 500.0, 240.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    info.binning_x = 2;
+    info.roi.width = 320;
+    info.roi.do_rectify = true;
+
+    let bytes = info.to_cdr().unwrap();
+    // D starts after the header (16), height, width (24) and distortion_model (38),
+    // its length is aligned to 40 and the first element to 48
+    assert_eq!(&bytes[4 + 40..4 + 44], &[5, 0, 0, 0]);
+    assert_eq!(&bytes[4 + 48..4 + 56], &0.1f64.to_le_bytes());
+
+    assert_eq!(CameraInfo::from_cdr(&bytes).unwrap(), info);
+}
+
+#[test]
+fn stamp_seconds_out_of_range_fail() {
+    // stamp.sec = -1
+    let mut bytes = IMAGE_LE;
+    bytes[4..8].copy_from_slice(&(-1i32).to_le_bytes());
+    assert!(Image::from_cdr(&bytes).is_err());
+
+    let mut image = Image::from_cdr(&IMAGE_LE).unwrap();
+    image.header.stamp.sec = i32::MAX as u32;
+    assert!(image.to_cdr().is_ok());
+    image.header.stamp.sec = i32::MAX as u32 + 1;
+    assert!(image.to_cdr().is_err());
+}
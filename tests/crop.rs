@@ -0,0 +1,100 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_geometry::PinholeCameraModel,
+    image_proc::crop,
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::{
+            CameraInfo,
+            RegionOfInterest,
+        },
+    },
+};
+
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: 8,
+        height: 6,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![0.0; 5],
+        K: [10.0, 0.0, 4.0, 0.0, 10.0, 3.0, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [10.0, 0.0, 4.0, 0.0, 0.0, 10.0, 3.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn mono16_image(width: usize, height: usize) -> CvImage {
+    let data = (0..(width * height) as u16).flat_map(|v| v.to_le_bytes()).collect();
+    CvImage::from_parts(Header::default(), height, width, "mono16", DataContainer::VecU8(data))
+}
+
+#[test]
+fn crop_copies_region() {
+    let image = mono16_image(8, 6);
+    let roi = RegionOfInterest { x_offset: 2, y_offset: 1, width: 3, height: 2, do_rectify: false };
+
+    let (cropped, _) = crop(&image, &camera_info(), &roi).unwrap();
+
+    let expected: Vec<u8> = [10u16, 11, 12, 18, 19, 20].iter().flat_map(|v| v.to_le_bytes()).collect();
+    assert_eq!((cropped.width(), cropped.height()), (3, 2));
+    assert_eq!(*cropped.as_container(), DataContainer::VecU8(expected));
+}
+
+#[test]
+fn crop_shifts_principal_point() {
+    let image = mono16_image(8, 6);
+    let roi = RegionOfInterest { x_offset: 2, y_offset: 1, width: 0, height: 0, do_rectify: false };
+
+    let (cropped, cropped_info) = crop(&image, &camera_info(), &roi).unwrap();
+    let model = PinholeCameraModel::from_camera_info(&cropped_info).unwrap();
+
+    assert_eq!((cropped.width(), cropped.height()), (6, 5));
+    assert_eq!(model.reduced_resolution(), (6, 5));
+    assert_eq!((model.cx(), model.cy()), (2.0, 2.0));
+    assert_eq!(cropped_info.K, camera_info().K);
+}
+
+#[test]
+fn crop_composes_with_binning_and_roi() {
+    let image = mono16_image(4, 3);
+    let info = CameraInfo {
+        binning_x: 2,
+        binning_y: 2,
+        roi: RegionOfInterest { x_offset: 2, y_offset: 0, width: 8, height: 6, do_rectify: false },
+        width: 10,
+        ..camera_info()
+    };
+    let roi = RegionOfInterest { x_offset: 1, y_offset: 1, width: 2, height: 2, do_rectify: true };
+
+    let (_, cropped_info) = crop(&image, &info, &roi).unwrap();
+
+    assert_eq!(cropped_info.roi, RegionOfInterest { x_offset: 4, y_offset: 2, width: 4, height: 4, do_rectify: true });
+}
+
+#[test]
+fn crop_outside_of_image_fails() {
+    let image = mono16_image(8, 6);
+    let roi = RegionOfInterest { x_offset: 6, y_offset: 0, width: 3, height: 1, do_rectify: false };
+
+    assert!(crop(&image, &camera_info(), &roi).is_err());
+}
+
+#[test]
+fn crop_with_short_data_fails() {
+    let roi = RegionOfInterest { x_offset: 0, y_offset: 0, width: 2, height: 1, do_rectify: false };
+
+    // One byte short of the last pixel
+    let mut data = match mono16_image(8, 6).into_parts().4 {
+        DataContainer::VecU8(data) => data,
+        container => panic!("unexpected container {:?}", container),
+    };
+    data.pop();
+    let image = CvImage::from_parts(Header::default(), 6, 8, "mono16", DataContainer::VecU8(data));
+    assert!(crop(&image, &camera_info(), &roi).is_err());
+
+    // Longer data is rejected as well, instead of being silently dropped
+    let image = CvImage::from_parts(Header::default(), 6, 8, "mono16", DataContainer::VecU8(vec![0; 8 * 6 * 2 + 2]));
+    assert!(crop(&image, &camera_info(), &roi).is_err());
+}
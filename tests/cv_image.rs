@@ -0,0 +1,46 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::{
+        sensor_msgs::Image,
+        std_msgs::Header,
+    },
+};
+
+fn mono16(header: Header, values: &[u16]) -> CvImage {
+    let data = values.iter().flat_map(|value| value.to_le_bytes()).collect();
+    CvImage::from_parts(header, 1, values.len(), "mono16", DataContainer::VecU8(data))
+}
+
+#[test]
+fn into_imgmsg_16_bit_byte_order() {
+    let image = mono16(Header::default(), &[0x0102, 0xa0b0]);
+
+    let big: Image = image.clone().into_imgmsg(1).unwrap();
+    assert_eq!(big.is_bigendian, 1);
+    assert_eq!(big.data, vec![0x01, 0x02, 0xa0, 0xb0]);
+
+    let little: Image = image.into_imgmsg(0).unwrap();
+    assert_eq!(little.is_bigendian, 0);
+    assert_eq!(little.data, vec![0x02, 0x01, 0xb0, 0xa0]);
+}
+
+#[test]
+fn into_imgmsg_rejects_invalid_endianness() {
+    let image = mono16(Header::default(), &[1]);
+
+    assert!(image.into_imgmsg::<Image>(2).is_err());
+}
+
+#[cfg(feature = "opencv")]
+#[test]
+fn to_cvimage_between_bit_depths_keeps_header() {
+    let header = Header { frame_id: "camera".to_string(), ..Default::default() };
+    let mut image = mono16(header, &[0, 25700, 65535]);
+
+    let converted = image.to_cvimage("mono8").unwrap();
+
+    assert_eq!(converted.encoding(), "mono8");
+    assert_eq!(converted.header().frame_id, "camera");
+    assert_eq!(*converted.as_container(), DataContainer::VecU8(vec![0, 100, 255]));
+}
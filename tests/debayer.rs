@@ -0,0 +1,53 @@
+#![cfg(feature = "opencv")]
+
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_proc::{
+        DebayerAlgorithm,
+        debayer,
+        debayer::bayer_to_bgr_code,
+    },
+    msgs::std_msgs::Header,
+    utils::image_encodings::Encoding,
+};
+use opencv::imgproc;
+
+#[test]
+fn bayer_pattern_to_code() {
+    let patterns = [
+        (Encoding::BayerRGGB, [imgproc::COLOR_BayerBG2BGR, imgproc::COLOR_BayerBG2BGR_EA, imgproc::COLOR_BayerBG2BGR_VNG]),
+        (Encoding::BayerBGGR, [imgproc::COLOR_BayerRG2BGR, imgproc::COLOR_BayerRG2BGR_EA, imgproc::COLOR_BayerRG2BGR_VNG]),
+        (Encoding::BayerGBRG, [imgproc::COLOR_BayerGR2BGR, imgproc::COLOR_BayerGR2BGR_EA, imgproc::COLOR_BayerGR2BGR_VNG]),
+        (Encoding::BayerGRBG, [imgproc::COLOR_BayerGB2BGR, imgproc::COLOR_BayerGB2BGR_EA, imgproc::COLOR_BayerGB2BGR_VNG]),
+    ];
+    let algorithms = [DebayerAlgorithm::Bilinear, DebayerAlgorithm::EdgeAware, DebayerAlgorithm::Vng];
+
+    for (pattern, codes) in patterns {
+        for (algorithm, code) in algorithms.iter().zip(codes) {
+            assert_eq!(bayer_to_bgr_code(pattern, *algorithm).unwrap(), code, "{:?} {:?}", pattern, algorithm);
+        }
+    }
+
+    assert!(bayer_to_bgr_code(Encoding::Bgr, DebayerAlgorithm::Bilinear).is_err());
+}
+
+#[test]
+fn debayer_recovers_flat_colors() {
+    // 4x4 rggb8 mosaic of a flat color with red 200, green 100 and blue 50
+    let data = (0..16)
+        .map(|i| match (i / 4 % 2, i % 4 % 2) {
+            (0, 0) => 200,
+            (1, 1) => 50,
+            _ => 100,
+        })
+        .collect();
+    let mut image = CvImage::from_parts(Header::default(), 4, 4, "bayer_rggb8", DataContainer::VecU8(data));
+
+    let color = debayer(&mut image, DebayerAlgorithm::Bilinear).unwrap();
+    assert_eq!(color.encoding(), "bgr8");
+    match color.as_container() {
+        DataContainer::VecU8(data) => assert_eq!(data[(4 + 1) * 3..(4 + 1) * 3 + 3], [50, 100, 200]),
+        container => panic!("unexpected container {:?}", container),
+    }
+}
@@ -0,0 +1,79 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    depth_image_proc::{
+        convert::DEFAULT_DEPTH_SCALE,
+        convert_metric,
+        convert_raw,
+    },
+    msgs::std_msgs::Header,
+};
+
+fn depth_mm(values: &[u16]) -> CvImage {
+    let data = values.iter().flat_map(|mm| mm.to_le_bytes()).collect();
+    CvImage::from_parts(Header::default(), 1, values.len(), "16UC1", DataContainer::VecU8(data))
+}
+
+#[test]
+fn millimeters_to_meters() {
+    let depth_m = convert_metric(&depth_mm(&[1500, 0, 65535]), DEFAULT_DEPTH_SCALE).unwrap();
+
+    assert_eq!(depth_m.encoding(), "32FC1");
+    match depth_m.as_container() {
+        DataContainer::VecF32(data) => {
+            assert_eq!(data[0], 1.5);
+            assert!(data[1].is_nan());
+            assert_eq!(data[2], 65.535);
+        },
+        _ => panic!("unexpected container"),
+    }
+}
+
+#[test]
+fn meters_to_millimeters() {
+    let depth_m = CvImage::from_parts(
+        Header::default(), 1, 6, "32FC1",
+        DataContainer::VecF32(vec![1.5, f32::NAN, 0.0, -1.0, 100.0, 0.0004])
+    );
+
+    let depth = convert_raw(&depth_m, DEFAULT_DEPTH_SCALE).unwrap();
+
+    assert_eq!(depth.encoding(), "16UC1");
+    assert_eq!(*depth.as_container(), *depth_mm(&[1500, 0, 0, 0, 0, 0]).as_container());
+}
+
+#[test]
+fn custom_scale_round_trip() {
+    // Tenths of millimeters, as published by some time-of-flight cameras
+    let original = depth_mm(&[12345, 0, 7]);
+
+    let depth_m = convert_metric(&original, 0.0001).unwrap();
+    let depth = convert_raw(&depth_m, 0.0001).unwrap();
+
+    assert_eq!(*depth.as_container(), *original.as_container());
+}
+
+#[test]
+fn wrong_encoding_fails() {
+    let image = CvImage::from_parts(Header::default(), 1, 1, "mono8", DataContainer::VecU8(vec![0]));
+
+    assert!(convert_metric(&image, DEFAULT_DEPTH_SCALE).is_err());
+    assert!(convert_raw(&image, DEFAULT_DEPTH_SCALE).is_err());
+}
+
+#[test]
+fn mono16_is_not_a_depth_encoding() {
+    let image = CvImage::from_parts(Header::default(), 1, 1, "mono16", DataContainer::VecU8(vec![0xdc, 0x05]));
+
+    assert!(convert_metric(&image, DEFAULT_DEPTH_SCALE).is_err());
+}
+
+#[test]
+fn invalid_scale_fails() {
+    let depth_m = CvImage::from_parts(Header::default(), 1, 1, "32FC1", DataContainer::VecF32(vec![1.5]));
+
+    for scale in [0.0, -0.001, f64::NAN, f64::INFINITY] {
+        assert!(convert_metric(&depth_mm(&[1500]), scale).is_err(), "scale {}", scale);
+        assert!(convert_raw(&depth_m, scale).is_err(), "scale {}", scale);
+    }
+}
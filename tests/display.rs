@@ -0,0 +1,148 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    display::{
+        DisplayOptions,
+        DisplayRange,
+    },
+    msgs::std_msgs::Header,
+};
+
+fn image(width: usize, encoding: &str, data: DataContainer) -> CvImage {
+    let header = Header {
+        frame_id: "camera".to_string(),
+        ..Default::default()
+    };
+    CvImage::from_parts(header, 1, width, encoding, data)
+}
+
+fn u16_image(encoding: &str, values: &[u16]) -> CvImage {
+    let channels = cv_bridge::utils::image_encodings::get_num_channels(encoding);
+    let data = values.iter().flat_map(|v| v.to_le_bytes()).collect();
+    image(values.len() / channels, encoding, DataContainer::VecU8(data))
+}
+
+fn bgr(image: &CvImage) -> &Vec<u8> {
+    assert_eq!(image.encoding(), "bgr8");
+    match image.as_container() {
+        DataContainer::VecU8(data) => data,
+        container => panic!("unexpected container {:?}", container),
+    }
+}
+
+#[test]
+fn auto_range_stretches_valid_values() {
+    let options = DisplayOptions {
+        invalid_color: [255, 0, 255],
+        ..Default::default()
+    };
+
+    // 0 is an invalid depth and does not take part in the range
+    let display = u16_image("16UC1", &[1000, 0, 2000, 1500]).to_display_image(&options).unwrap();
+
+    assert_eq!(display.header().frame_id, "camera");
+    assert_eq!(*bgr(&display), vec![0, 0, 0, 255, 0, 255, 255, 255, 255, 128, 128, 128]);
+}
+
+#[test]
+fn fixed_range_clamps() {
+    let options = DisplayOptions {
+        range: DisplayRange::Fixed { min: 1.0, max: 3.0 },
+        ..Default::default()
+    };
+    let mut depth = image(5, "32FC1", DataContainer::VecF32(vec![0.0, 1.0, 2.0, 5.0, f32::NAN]));
+
+    let display = depth.to_display_image(&options).unwrap();
+
+    assert_eq!(*bgr(&display), vec![0, 0, 0, 0, 0, 0, 128, 128, 128, 255, 255, 255, 0, 0, 0]);
+}
+
+#[test]
+fn encoding_range() {
+    let options = DisplayOptions {
+        range: DisplayRange::Encoding,
+        ..Default::default()
+    };
+
+    // 0 is a valid mono16 value, unlike in 16UC1 depth images
+    let display = u16_image("mono16", &[0, 32896, 65535]).to_display_image(&options).unwrap();
+    assert_eq!(*bgr(&display), vec![0, 0, 0, 128, 128, 128, 255, 255, 255]);
+
+    let display = image(2, "32FC1", DataContainer::VecF32(vec![0.5, 2.0])).to_display_image(&options).unwrap();
+    assert_eq!(*bgr(&display), vec![128, 128, 128, 255, 255, 255]);
+}
+
+#[test]
+fn generic_multi_channel_images_are_scaled_per_channel() {
+    let options = DisplayOptions {
+        range: DisplayRange::Encoding,
+        ..Default::default()
+    };
+
+    let display = u16_image("16UC3", &[0, 32896, 65535]).to_display_image(&options).unwrap();
+    assert_eq!(*bgr(&display), vec![0, 128, 255]);
+
+    // The alpha channel is dropped
+    let display = image(1, "8UC4", DataContainer::VecU8(vec![1, 2, 3, 4])).to_display_image(&options).unwrap();
+    assert_eq!(*bgr(&display), vec![1, 2, 3]);
+}
+
+#[test]
+fn generic_float_images_use_one_range_for_all_channels() {
+    let options = DisplayOptions {
+        invalid_color: [0, 0, 255],
+        ..Default::default()
+    };
+    let data = vec![
+        1.0, 2.0, 3.0, 100.0,
+        5.0, 1.0, 3.0, -100.0,
+        f32::NAN, 1.0, 1.0, 0.0,
+    ];
+
+    // The alpha values are outside of the range of the color channels
+    let display = image(3, "32FC4", DataContainer::VecF32(data)).to_display_image(&options).unwrap();
+
+    assert_eq!(*bgr(&display), vec![0, 64, 128, 255, 0, 128, 0, 0, 255]);
+}
+
+#[test]
+fn bgr8_is_copied() {
+    let data = DataContainer::VecU8(vec![1, 2, 3]);
+    let display = image(1, "bgr8", data.clone()).to_display_image(&DisplayOptions::default()).unwrap();
+
+    assert_eq!(*display.as_container(), data);
+}
+
+#[test]
+fn unsupported_encodings_fail() {
+    let options = DisplayOptions::default();
+
+    assert!(image(1, "8UC2", DataContainer::VecU8(vec![1, 2])).to_display_image(&options).is_err());
+}
+
+#[cfg(not(feature = "opencv"))]
+#[test]
+fn colormaps_and_color_conversions_need_opencv() {
+    let options = DisplayOptions {
+        colormap: Some(2),
+        ..Default::default()
+    };
+
+    assert!(u16_image("16UC1", &[1000]).to_display_image(&options).is_err());
+    assert!(image(1, "rgb8", DataContainer::VecU8(vec![1, 2, 3])).to_display_image(&DisplayOptions::default()).is_err());
+}
+
+#[cfg(feature = "opencv")]
+#[test]
+fn colormap_keeps_invalid_color() {
+    let options = DisplayOptions {
+        colormap: Some(opencv::imgproc::COLORMAP_JET),
+        invalid_color: [1, 2, 3],
+        ..Default::default()
+    };
+
+    let display = u16_image("16UC1", &[1000, 0, 2000]).to_display_image(&options).unwrap();
+
+    assert_eq!(bgr(&display)[3..6], [1, 2, 3]);
+    assert_ne!(bgr(&display)[0..3], bgr(&display)[6..9]);
+}
@@ -0,0 +1,17 @@
+#![cfg(feature = "opencv")]
+
+use cv_bridge::utils::image_encodings::{
+    get_conversion_code,
+    Encoding,
+};
+use opencv::imgproc;
+
+#[test]
+fn yuv422_conversion_codes() {
+    assert_eq!(get_conversion_code(Encoding::Yuv422, Encoding::Bgr).unwrap(), imgproc::COLOR_YUV2BGR_UYVY);
+    assert_eq!(get_conversion_code(Encoding::Yuv422, Encoding::Bgra).unwrap(), imgproc::COLOR_YUV2BGRA_UYVY);
+    assert_eq!(get_conversion_code(Encoding::Yuv422, Encoding::Rgb).unwrap(), imgproc::COLOR_YUV2RGB_UYVY);
+    assert_eq!(get_conversion_code(Encoding::Yuv422, Encoding::Rgba).unwrap(), imgproc::COLOR_YUV2RGBA_UYVY);
+    assert_eq!(get_conversion_code(Encoding::Yuv422yuy2, Encoding::Bgr).unwrap(), imgproc::COLOR_YUV2BGR_YUY2);
+    assert_eq!(get_conversion_code(Encoding::Yuv422yuy2, Encoding::Bgra).unwrap(), imgproc::COLOR_YUV2BGRA_YUY2);
+}
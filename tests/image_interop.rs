@@ -0,0 +1,93 @@
+#![cfg(feature = "image")]
+
+use std::convert::TryFrom;
+
+use image::{
+    DynamicImage,
+    GrayImage,
+    ImageBuffer,
+    Luma,
+    Rgb,
+    RgbImage,
+    RgbaImage,
+};
+
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::std_msgs::Header,
+};
+
+fn cv_image(width: usize, encoding: &str, data: Vec<u8>) -> CvImage {
+    CvImage::from_parts(Header::default(), 1, width, encoding, DataContainer::VecU8(data))
+}
+
+#[test]
+fn rgb8_round_trip() {
+    let data = vec![1, 2, 3, 4, 5, 6];
+
+    let rgb = RgbImage::try_from(cv_image(2, "rgb8", data.clone())).unwrap();
+    assert_eq!(rgb.dimensions(), (2, 1));
+    assert_eq!(rgb.get_pixel(1, 0), &Rgb([4, 5, 6]));
+
+    let round_trip = CvImage::from(rgb);
+    assert_eq!(round_trip.encoding(), "rgb8");
+    assert_eq!((round_trip.width(), round_trip.height()), (2, 1));
+    assert_eq!(*round_trip.as_container(), DataContainer::VecU8(data));
+}
+
+#[test]
+fn bgr8_is_swapped_to_rgb() {
+    let rgb = RgbImage::try_from(cv_image(2, "bgr8", vec![1, 2, 3, 4, 5, 6])).unwrap();
+    assert_eq!(rgb.into_raw(), vec![3, 2, 1, 6, 5, 4]);
+
+    let rgba = RgbaImage::try_from(cv_image(1, "bgra8", vec![1, 2, 3, 4])).unwrap();
+    assert_eq!(rgba.into_raw(), vec![3, 2, 1, 4]);
+
+    // Conversions back always produce rgb encoded images
+    let dynamic = DynamicImage::try_from(cv_image(1, "bgr8", vec![1, 2, 3])).unwrap();
+    let round_trip = CvImage::try_from(dynamic).unwrap();
+    assert_eq!(round_trip.encoding(), "rgb8");
+    assert_eq!(*round_trip.as_container(), DataContainer::VecU8(vec![3, 2, 1]));
+}
+
+#[test]
+fn mono16_is_little_endian() {
+    let gray = ImageBuffer::<Luma<u16>, Vec<u16>>::try_from(cv_image(2, "mono16", vec![0x02, 0x01, 0xb0, 0xa0])).unwrap();
+    assert_eq!(gray.into_raw(), vec![0x0102, 0xa0b0]);
+
+    let image = ImageBuffer::<Luma<u16>, Vec<u16>>::from_raw(2, 1, vec![0x0102, 0xa0b0]).unwrap();
+    let cv_image = CvImage::from(image);
+    assert_eq!(cv_image.encoding(), "mono16");
+    assert_eq!(*cv_image.as_container(), DataContainer::VecU8(vec![0x02, 0x01, 0xb0, 0xa0]));
+}
+
+#[test]
+fn rgb16_is_little_endian() {
+    let bgr16 = cv_image(1, "bgr16", vec![0x01, 0x00, 0x02, 0x00, 0x03, 0x01]);
+    let rgb = ImageBuffer::<Rgb<u16>, Vec<u16>>::try_from(bgr16).unwrap();
+    assert_eq!(rgb.into_raw(), vec![0x0103, 0x0002, 0x0001]);
+
+    let image = ImageBuffer::<Rgb<u16>, Vec<u16>>::from_raw(1, 1, vec![0x0103, 0x0002, 0x0001]).unwrap();
+    let cv_image = CvImage::from(image);
+    assert_eq!(cv_image.encoding(), "rgb16");
+    assert_eq!(*cv_image.as_container(), DataContainer::VecU8(vec![0x03, 0x01, 0x02, 0x00, 0x01, 0x00]));
+}
+
+#[test]
+fn rejects_unsupported_encodings() {
+    assert!(GrayImage::try_from(cv_image(1, "rgb8", vec![1, 2, 3])).is_err());
+    assert!(RgbImage::try_from(cv_image(1, "rgba8", vec![1, 2, 3, 4])).is_err());
+    assert!(RgbaImage::try_from(cv_image(1, "rgb8", vec![1, 2, 3])).is_err());
+    assert!(DynamicImage::try_from(cv_image(1, "yuv422", vec![1, 2])).is_err());
+    assert!(DynamicImage::try_from(cv_image(1, "8UC3", vec![1, 2, 3])).is_err());
+
+    let float = CvImage::from_parts(Header::default(), 1, 1, "32FC1", DataContainer::VecF32(vec![1.0]));
+    assert!(DynamicImage::try_from(float).is_err());
+
+    // Data that does not match the dimensions
+    assert!(RgbImage::try_from(cv_image(2, "rgb8", vec![1, 2, 3])).is_err());
+
+    let la = DynamicImage::ImageLumaA8(ImageBuffer::from_raw(1, 1, vec![1, 2]).unwrap());
+    assert!(CvImage::try_from(la).is_err());
+}
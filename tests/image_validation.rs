@@ -0,0 +1,69 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::sensor_msgs::Image,
+    utils::image_validation::validate_imgmsg,
+};
+
+fn image(encoding: &str, width: u32, height: u32, step: u32, len: usize) -> Image {
+    Image {
+        height,
+        width,
+        encoding: encoding.to_string(),
+        step,
+        data: vec![0; len],
+        ..Default::default()
+    }
+}
+
+fn error(image: &Image) -> String {
+    validate_imgmsg(image).unwrap_err().to_string()
+}
+
+#[test]
+fn valid_messages() {
+    assert!(validate_imgmsg(&image("bgr8", 4, 2, 12, 24)).is_ok());
+    assert!(validate_imgmsg(&image("mono16", 3, 2, 8, 16)).is_ok());
+    assert!(validate_imgmsg(&image("yuv422", 2, 1, 4, 4)).is_ok());
+    assert!(validate_imgmsg(&image("32FC1", 0, 0, 0, 0)).is_ok());
+}
+
+#[test]
+fn malformed_messages() {
+    assert_eq!(error(&image("bgr9", 4, 2, 12, 24)), "Unknown encoding [encoding: bgr9]");
+    assert_eq!(
+        error(&image("bgr8", 4, 2, 11, 22)),
+        "Step is smaller than the row length [step: 11, width: 4, bytes per pixel: 3]"
+    );
+    assert_eq!(
+        error(&image("bgr8", 4, 2, 12, 23)),
+        "Data length does not match step * height [data length: 23, step: 12, height: 2]"
+    );
+    assert_eq!(
+        error(&image("yuv422", 3, 1, 6, 6)),
+        "YUV 4:2:2 images must have an even width [encoding: yuv422, width: 3]"
+    );
+
+    let mut big = image("mono8", 1, 1, 1, 1);
+    big.is_bigendian = 2;
+    assert_eq!(error(&big), "Unsupported endianness [is_bigendian: 2]");
+}
+
+#[test]
+fn from_imgmsg_rejects_malformed_messages() {
+    // Claims 1000x1000 pixels with only 4 bytes of data
+    let image = image("mono8", 1000, 1000, 1000, 4);
+
+    assert!(CvImage::from_imgmsg(image).is_err());
+}
+
+#[test]
+fn from_imgmsg_removes_row_padding() {
+    let mut padded = image("mono16", 2, 2, 6, 12);
+    padded.is_bigendian = 1;
+    padded.data = vec![0, 1, 0, 2, 0xff, 0xff, 0, 3, 0, 4, 0xff, 0xff];
+
+    let cv_image = CvImage::from_imgmsg(padded).unwrap();
+
+    assert_eq!(*cv_image.as_container(), DataContainer::VecU8(vec![1, 0, 2, 0, 3, 0, 4, 0]));
+}
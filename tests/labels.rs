@@ -0,0 +1,59 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    labels::LabelPalette,
+    msgs::std_msgs::Header,
+};
+
+fn labels16(ids: &[u16]) -> CvImage {
+    let data = ids.iter().flat_map(|id| id.to_le_bytes()).collect();
+    CvImage::from_parts(Header::default(), 1, ids.len(), "16UC1", DataContainer::VecU8(data))
+}
+
+#[test]
+fn deterministic_palette() {
+    let palette = LabelPalette::Deterministic;
+
+    // PASCAL VOC colors in bgr order
+    assert_eq!(palette.color(0), [0, 0, 0]);
+    assert_eq!(palette.color(1), [0, 0, 128]);
+    assert_eq!(palette.color(2), [0, 128, 0]);
+    assert_eq!(palette.color(15), [128, 128, 192]);
+    assert_ne!(palette.color(300), palette.color(301));
+}
+
+#[test]
+fn colorize_with_custom_palette() {
+    let mut labels = labels16(&[0, 1, 2]);
+    labels.header_mut().frame_id = "camera".to_string();
+    let palette = LabelPalette::Custom(vec![[1, 2, 3], [4, 5, 6]]);
+
+    let colorized = labels.colorize_labels(&palette).unwrap();
+
+    assert_eq!(colorized.encoding(), "bgr8");
+    assert_eq!(colorized.header().frame_id, "camera");
+    // Label 2 is past the end of the palette
+    assert_eq!(*colorized.as_container(), DataContainer::VecU8(vec![1, 2, 3, 4, 5, 6, 0, 128, 0]));
+}
+
+#[test]
+fn overlay_blends_over_color_image() {
+    let labels = CvImage::from_parts(Header::default(), 1, 2, "mono8", DataContainer::VecU8(vec![0, 1]));
+    let color = CvImage::from_parts(Header::default(), 1, 2, "rgb8", DataContainer::VecU8(vec![10, 20, 30, 200, 100, 0]));
+    let palette = LabelPalette::Custom(vec![[0, 0, 0], [255, 0, 0]]);
+
+    let overlay = labels.overlay_labels(&color, &palette, 0.5, Some(0)).unwrap();
+
+    assert_eq!(overlay.encoding(), "bgr8");
+    assert_eq!(*overlay.as_container(), DataContainer::VecU8(vec![30, 20, 10, 128, 50, 100]));
+}
+
+#[test]
+fn invalid_inputs_fail() {
+    let palette = LabelPalette::Deterministic;
+    let color = CvImage::from_parts(Header::default(), 1, 3, "bgr8", DataContainer::VecU8(vec![0; 9]));
+
+    assert!(labels16(&[0, 1]).overlay_labels(&color, &palette, 0.5, None).is_err());
+    assert!(labels16(&[0, 1, 2]).overlay_labels(&color, &palette, 1.5, None).is_err());
+    assert!(color.colorize_labels(&palette).is_err());
+}
@@ -0,0 +1,103 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    msgs::std_msgs::{
+        Float32MultiArray,
+        Header,
+        MultiArrayDimension,
+        MultiArrayLayout,
+        UInt8MultiArray,
+    },
+    multi_array::ChannelOrder,
+};
+
+fn dimension(label: &str, size: u32, stride: u32) -> MultiArrayDimension {
+    MultiArrayDimension { label: label.to_string(), size, stride }
+}
+
+#[test]
+fn padded_2d_array() {
+    // 2x2 array with one padding element at the front and at the end of each row
+    let array = Float32MultiArray {
+        layout: MultiArrayLayout {
+            dim: vec![dimension("height", 2, 6), dimension("width", 2, 3)],
+            data_offset: 1,
+        },
+        data: vec![-1.0, 1.0, 2.0, -1.0, 3.0, 4.0, -1.0],
+    };
+
+    let image = CvImage::from_float32_multiarray(&array, ChannelOrder::Last).unwrap();
+
+    assert_eq!(image.encoding(), "32FC1");
+    assert_eq!((image.width(), image.height()), (2, 2));
+    assert_eq!(*image.as_container(), DataContainer::VecF32(vec![1.0, 2.0, 3.0, 4.0]));
+}
+
+#[test]
+fn channel_first_array() {
+    // 2 channels of 1x3 pixels
+    let array = UInt8MultiArray {
+        layout: MultiArrayLayout {
+            dim: vec![dimension("channel", 2, 6), dimension("height", 1, 3), dimension("width", 3, 3)],
+            data_offset: 0,
+        },
+        data: vec![1, 2, 3, 10, 20, 30],
+    };
+
+    let image = CvImage::from_uint8_multiarray(&array, ChannelOrder::First).unwrap();
+
+    assert_eq!(image.encoding(), "8UC2");
+    assert_eq!(*image.as_container(), DataContainer::VecU8(vec![1, 10, 2, 20, 3, 30]));
+
+    let round_trip = image.to_uint8_multiarray(ChannelOrder::First).unwrap();
+    assert_eq!(round_trip, array);
+}
+
+#[test]
+fn image_to_array_round_trip() {
+    let data: Vec<f32> = (0..24).map(|v| v as f32 * 0.5).collect();
+    let image = CvImage::from_parts(Header::default(), 2, 4, "32FC3", DataContainer::VecF32(data));
+
+    for order in [ChannelOrder::Last, ChannelOrder::First] {
+        let array = image.to_float32_multiarray(order).unwrap();
+        assert_eq!(array.layout.dim.len(), 3);
+        assert_eq!(array.layout.dim[0].stride, 24);
+
+        let converted = CvImage::from_float32_multiarray(&array, order).unwrap();
+        assert_eq!(converted.encoding(), "32FC3");
+        assert_eq!(*converted.as_container(), *image.as_container());
+    }
+}
+
+#[test]
+fn invalid_arrays_fail() {
+    let short = Float32MultiArray {
+        layout: MultiArrayLayout {
+            dim: vec![dimension("height", 2, 4), dimension("width", 2, 2)],
+            data_offset: 1,
+        },
+        data: vec![0.0; 4],
+    };
+    let too_many_channels = Float32MultiArray {
+        layout: MultiArrayLayout {
+            dim: vec![dimension("height", 1, 5), dimension("width", 1, 5), dimension("channel", 5, 5)],
+            data_offset: 0,
+        },
+        data: vec![0.0; 5],
+    };
+    let one_dimension = Float32MultiArray {
+        layout: MultiArrayLayout {
+            dim: vec![dimension("x", 3, 3)],
+            data_offset: 0,
+        },
+        data: vec![0.0; 3],
+    };
+
+    assert!(CvImage::from_float32_multiarray(&short, ChannelOrder::Last).is_err());
+    assert!(CvImage::from_float32_multiarray(&too_many_channels, ChannelOrder::Last).is_err());
+    assert!(CvImage::from_float32_multiarray(&one_dimension, ChannelOrder::Last).is_err());
+
+    let mono16 = CvImage::from_parts(Header::default(), 1, 1, "mono16", DataContainer::VecU8(vec![0, 0]));
+    assert!(mono16.to_uint8_multiarray(ChannelOrder::Last).is_err());
+    assert!(mono16.to_float32_multiarray(ChannelOrder::Last).is_err());
+}
@@ -0,0 +1,78 @@
+use cv_bridge::{
+    image_geometry::PinholeCameraModel,
+    msgs::sensor_msgs::CameraInfo,
+};
+
+fn camera_info(distortion_model: &str, d: Vec<f64>) -> CameraInfo {
+    CameraInfo {
+        width: 640,
+        height: 480,
+        distortion_model: distortion_model.to_string(),
+        D: d,
+        K: [525.0, 0.0, 319.5, 0.0, 520.0, 239.5, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [510.0, 0.0, 318.0, 0.0, 0.0, 505.0, 241.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn assert_close(a: (f64, f64), b: (f64, f64)) {
+    assert!((a.0 - b.0).abs() < 1e-6 && (a.1 - b.1).abs() < 1e-6, "{:?} != {:?}", a, b);
+}
+
+#[test]
+fn project_and_back_project() {
+    let model = PinholeCameraModel::from_camera_info(&camera_info("plumb_bob", vec![])).unwrap();
+
+    let uv = model.project_3d_to_pixel((0.5, -0.25, 2.0));
+    assert_close(uv, (510.0 * 0.25 + 318.0, 505.0 * -0.125 + 241.0));
+
+    let (x, y, z) = model.project_pixel_to_3d_ray(uv);
+    assert_close((x, y), (0.25, -0.125));
+    assert_eq!(z, 1.0);
+}
+
+#[test]
+fn rectify_unrectify_round_trip() {
+    let models = [
+        ("plumb_bob", vec![-0.28, 0.07, 0.001, -0.0005, 0.0]),
+        ("rational_polynomial", vec![-0.28, 0.07, 0.001, -0.0005, 0.01, 0.02, 0.001, 0.0005]),
+        ("equidistant", vec![0.05, -0.01, 0.002, -0.0003]),
+    ];
+
+    for (distortion_model, d) in models {
+        let model = PinholeCameraModel::from_camera_info(&camera_info(distortion_model, d)).unwrap();
+
+        for uv_rect in [(320.0, 240.0), (100.0, 50.0), (600.0, 400.0)] {
+            let uv_raw = model.unrectify_point(uv_rect);
+            assert_close(model.rectify_point(uv_raw), uv_rect);
+        }
+    }
+}
+
+#[test]
+fn binning_and_roi_adjust_intrinsics() {
+    let mut info = camera_info("plumb_bob", vec![]);
+    info.binning_x = 2;
+    info.binning_y = 2;
+    info.roi.x_offset = 100;
+    info.roi.y_offset = 50;
+    info.roi.width = 400;
+    info.roi.height = 300;
+
+    let model = PinholeCameraModel::from_camera_info(&info).unwrap();
+    assert_eq!(model.fx(), 255.0);
+    assert_eq!(model.cx(), (318.0 - 100.0) / 2.0);
+    assert_eq!(model.cy(), (241.0 - 50.0) / 2.0);
+    assert_eq!(model.full_projection_matrix(), &info.P);
+    assert_eq!(model.reduced_resolution(), (200, 150));
+
+    let uv_full = (300.0, 200.0);
+    assert_close(model.to_full_resolution(model.to_reduced_resolution(uv_full)), uv_full);
+}
+
+#[test]
+fn invalid_calibration_fails() {
+    assert!(PinholeCameraModel::from_camera_info(&CameraInfo::default()).is_err());
+    assert!(PinholeCameraModel::from_camera_info(&camera_info("unknown", vec![0.1])).is_err());
+}
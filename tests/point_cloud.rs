@@ -0,0 +1,98 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    depth_image_proc::point_cloud,
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::{
+            CameraInfo,
+            PointCloud2,
+            PointField,
+        },
+    },
+};
+
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: 2,
+        height: 2,
+        K: [2.0, 0.0, 0.5, 0.0, 4.0, 0.5, 0.0, 0.0, 1.0],
+        P: [2.0, 0.0, 0.5, 0.0, 0.0, 4.0, 0.5, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn header() -> Header {
+    Header { frame_id: "depth_optical".to_string(), ..Default::default() }
+}
+
+fn point(cloud: &PointCloud2, index: usize) -> [f32; 3] {
+    let start = index * cloud.point_step as usize;
+    let value = |offset: usize| {
+        let bytes = &cloud.data[start + offset..start + offset + 4];
+        f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+    };
+
+    [value(0), value(4), value(8)]
+}
+
+#[test]
+fn xyz_from_millimeters() {
+    let depth_mm: Vec<u8> = [1000u16, 0, 2000, 500].iter().flat_map(|v| v.to_le_bytes()).collect();
+    let depth = CvImage::from_parts(header(), 2, 2, "16UC1", DataContainer::VecU8(depth_mm));
+
+    let cloud = point_cloud(&depth, &camera_info(), None).unwrap();
+
+    assert_eq!(cloud.header.frame_id, "depth_optical");
+    assert_eq!((cloud.width, cloud.height, cloud.point_step, cloud.row_step), (2, 2, 16, 32));
+    assert_eq!(cloud.fields.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(), vec!["x", "y", "z"]);
+    assert!(cloud.fields.iter().all(|f| f.datatype == PointField::FLOAT32));
+    assert!(!cloud.is_dense);
+
+    assert_eq!(point(&cloud, 0), [-0.25, -0.125, 1.0]);
+    assert!(point(&cloud, 1).iter().all(|v| v.is_nan()));
+    assert_eq!(point(&cloud, 2), [-0.5, 0.25, 2.0]);
+    assert_eq!(point(&cloud, 3), [0.125, 0.0625, 0.5]);
+}
+
+#[test]
+fn xyzrgb_from_meters() {
+    let depth = CvImage::from_parts(header(), 2, 2, "32FC1", DataContainer::VecF32(vec![1.0, f32::NAN, 0.0, 2.0]));
+    let color = CvImage::from_parts(header(), 2, 2, "bgr8", DataContainer::VecU8((0..12).collect()));
+
+    let cloud = point_cloud(&depth, &camera_info(), Some(&color)).unwrap();
+
+    assert_eq!((cloud.point_step, cloud.row_step), (32, 64));
+    assert_eq!(cloud.fields[3].name, "rgb");
+    assert_eq!(cloud.fields[3].offset, 16);
+    assert!(point(&cloud, 1).iter().all(|v| v.is_nan()));
+    assert!(point(&cloud, 2).iter().all(|v| v.is_nan()));
+    assert_eq!(point(&cloud, 3), [0.5, 0.25, 2.0]);
+
+    // rgb packed as 0x00RRGGBB in little endian, the last pixel is b=9, g=10, r=11
+    let rgb = &cloud.data[3 * 32 + 16..3 * 32 + 20];
+    assert_eq!(u32::from_le_bytes([rgb[0], rgb[1], rgb[2], rgb[3]]), 0x000b0a09);
+}
+
+#[test]
+fn mismatched_color_size_fails() {
+    let depth = CvImage::from_parts(header(), 2, 2, "32FC1", DataContainer::VecF32(vec![1.0; 4]));
+    let color = CvImage::from_parts(header(), 1, 1, "mono8", DataContainer::VecU8(vec![0]));
+
+    assert!(point_cloud(&depth, &camera_info(), Some(&color)).is_err());
+}
+
+#[test]
+fn mismatched_data_length_fails() {
+    // One depth value short of the 2x2 image
+    let depth = CvImage::from_parts(header(), 2, 2, "32FC1", DataContainer::VecF32(vec![1.0; 3]));
+    assert!(point_cloud(&depth, &camera_info(), None).is_err());
+
+    let depth_mm = CvImage::from_parts(header(), 2, 2, "16UC1", DataContainer::VecU8(vec![0; 7]));
+    assert!(point_cloud(&depth_mm, &camera_info(), None).is_err());
+
+    // Color image of the right size, but with the data of 3 pixels
+    let depth = CvImage::from_parts(header(), 2, 2, "32FC1", DataContainer::VecF32(vec![1.0; 4]));
+    let color = CvImage::from_parts(header(), 2, 2, "bgr8", DataContainer::VecU8(vec![0; 9]));
+    assert!(point_cloud(&depth, &camera_info(), Some(&color)).is_err());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "opencv")]
+
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_proc::{
+        Interpolation,
+        Rectifier,
+    },
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::CameraInfo,
+    },
+};
+
+/// Distortion free calibration of an 8x4 camera, so rectifying is the identity
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: 8,
+        height: 4,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![0.0; 5],
+        K: [10.0, 0.0, 4.0, 0.0, 10.0, 2.0, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [10.0, 0.0, 4.0, 0.0, 0.0, 10.0, 2.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn image(width: usize, height: usize, encoding: &str) -> CvImage {
+    let data = (0..width * height).map(|i| (i % width) as u8 * 10 + 1).collect();
+    CvImage::from_parts(Header::default(), height, width, encoding, DataContainer::VecU8(data))
+}
+
+fn row(image: &CvImage) -> Vec<u8> {
+    match image.as_container() {
+        DataContainer::VecU8(data) => data[..image.width()].to_vec(),
+        container => panic!("unexpected container {:?}", container),
+    }
+}
+
+#[test]
+fn calibration_change_recomputes_maps() {
+    let mut rectifier = Rectifier::new(Interpolation::Nearest);
+    let mut info = camera_info();
+
+    let rectified = rectifier.rectify_image(&mut image(8, 4, "mono8"), &info).unwrap();
+    assert_eq!(row(&rectified), vec![1, 11, 21, 31, 41, 51, 61, 71]);
+
+    // Moving the rectified principal point one pixel right shifts the image
+    info.P[2] = 5.0;
+    let rectified = rectifier.rectify_image(&mut image(8, 4, "mono8"), &info).unwrap();
+    assert_eq!(row(&rectified), vec![0, 1, 11, 21, 31, 41, 51, 61]);
+
+    // Undistorting uses K, so it is not shifted
+    let undistorted = rectifier.undistort(&mut image(8, 4, "mono8"), &info).unwrap();
+    assert_eq!(row(&undistorted), vec![1, 11, 21, 31, 41, 51, 61, 71]);
+}
+
+#[test]
+fn size_mismatch_fails_with_cached_maps() {
+    let mut rectifier = Rectifier::new(Interpolation::Nearest);
+    let info = camera_info();
+
+    assert!(rectifier.rectify_image(&mut image(4, 4, "mono8"), &info).is_err());
+    assert!(rectifier.rectify_image(&mut image(8, 4, "mono8"), &info).is_ok());
+    assert!(rectifier.rectify_image(&mut image(4, 4, "mono8"), &info).is_err());
+}
+
+#[test]
+fn unsupported_inputs_fail() {
+    let info = camera_info();
+
+    let mut rectifier = Rectifier::new(Interpolation::Area);
+    assert!(rectifier.rectify_image(&mut image(8, 4, "mono8"), &info).is_err());
+
+    let mut rectifier = Rectifier::new(Interpolation::Linear);
+    assert!(rectifier.rectify_image(&mut image(8, 4, "bayer_rggb8"), &info).is_err());
+    assert!(rectifier.rectify_image(&mut image(8, 4, "yuv422"), &info).is_err());
+}
@@ -0,0 +1,98 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    depth_image_proc::register,
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::CameraInfo,
+    },
+};
+
+const IDENTITY: [f64; 16] = [
+    1.0, 0.0, 0.0, 0.0,
+    0.0, 1.0, 0.0, 0.0,
+    0.0, 0.0, 1.0, 0.0,
+    0.0, 0.0, 0.0, 1.0,
+];
+
+fn camera_info(frame_id: &str) -> CameraInfo {
+    CameraInfo {
+        header: Header { frame_id: frame_id.to_string(), ..Default::default() },
+        width: 4,
+        height: 1,
+        K: [2.0, 0.0, 1.5, 0.0, 2.0, 0.0, 0.0, 0.0, 1.0],
+        P: [2.0, 0.0, 1.5, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn depth_image(depth: Vec<f32>) -> CvImage {
+    let header = Header { seq: 3, frame_id: "depth_optical".to_string(), ..Default::default() };
+    CvImage::from_parts(header, 1, depth.len(), "32FC1", DataContainer::VecF32(depth))
+}
+
+fn values(image: &CvImage) -> Vec<f32> {
+    match image.as_container() {
+        DataContainer::VecF32(data) => data.clone(),
+        _ => panic!("unexpected container"),
+    }
+}
+
+#[test]
+fn identity_keeps_depth() {
+    let depth = depth_image(vec![1.0, 2.0, f32::NAN, 0.5]);
+
+    let (registered, registered_info) = register(&depth, &camera_info("depth_optical"), &camera_info("color_optical"), &IDENTITY).unwrap();
+
+    let registered_values = values(&registered);
+    assert_eq!(registered_values[0], 1.0);
+    assert_eq!(registered_values[1], 2.0);
+    assert!(registered_values[2].is_nan());
+    assert_eq!(registered_values[3], 0.5);
+    assert_eq!(registered.header().frame_id, "color_optical");
+    assert_eq!(registered.header().seq, 3);
+    assert_eq!(registered_info.header, *registered.header());
+}
+
+#[test]
+fn translation_keeps_nearest_depth() {
+    // Color camera 0.5 m to the left, pixels move right by 1 / z
+    let mut depth_to_color = IDENTITY;
+    depth_to_color[3] = 0.5;
+    let depth = depth_image(vec![1.0, 0.5, 2.0, f32::NAN]);
+
+    let (registered, _) = register(&depth, &camera_info("depth_optical"), &camera_info("color_optical"), &depth_to_color).unwrap();
+
+    // Pixel 1 (0.5 m) and pixel 2 (2 m) both land on pixel 3, the nearest one occludes
+    let registered_values = values(&registered);
+    assert!(registered_values[0].is_nan());
+    assert_eq!(registered_values[1], 1.0);
+    assert!(registered_values[2].is_nan());
+    assert_eq!(registered_values[3], 0.5);
+}
+
+#[test]
+fn millimeters_stay_millimeters() {
+    let data = [1000u16, 0, 0, 250].iter().flat_map(|mm| mm.to_le_bytes()).collect();
+    let depth = CvImage::from_parts(Header::default(), 1, 4, "16UC1", DataContainer::VecU8(data));
+
+    let (registered, _) = register(&depth, &camera_info("depth_optical"), &camera_info("color_optical"), &IDENTITY).unwrap();
+
+    assert_eq!(registered.encoding(), "16UC1");
+    assert_eq!(*registered.as_container(), *depth.as_container());
+}
+
+#[test]
+fn millimeters_out_of_range_are_invalid() {
+    // Color camera 1 m behind the depth camera, pushing the 65 m point past 65.535 m
+    let mut depth_to_color = IDENTITY;
+    depth_to_color[11] = 1.0;
+
+    let data = [0u16, 65000, 1000, 0].iter().flat_map(|mm| mm.to_le_bytes()).collect();
+    let depth = CvImage::from_parts(Header::default(), 1, 4, "16UC1", DataContainer::VecU8(data));
+
+    let (registered, _) = register(&depth, &camera_info("depth_optical"), &camera_info("color_optical"), &depth_to_color).unwrap();
+
+    let expected = [0u16, 0, 2000, 0].iter().flat_map(|mm| mm.to_le_bytes()).collect();
+    assert_eq!(*registered.as_container(), DataContainer::VecU8(expected));
+}
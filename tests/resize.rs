@@ -0,0 +1,115 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_geometry::PinholeCameraModel,
+    image_proc::{
+        Interpolation,
+        decimate,
+        resize,
+    },
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::{
+            CameraInfo,
+            RegionOfInterest,
+        },
+    },
+};
+
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: 8,
+        height: 4,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![0.0; 5],
+        K: [10.0, 0.0, 4.0, 0.0, 12.0, 2.0, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [10.0, 0.0, 4.0, -1.0, 0.0, 12.0, 2.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn mono16_image(width: usize, height: usize) -> CvImage {
+    let data = (0..(width * height) as u16).flat_map(|v| (v * 100).to_le_bytes()).collect();
+    CvImage::from_parts(Header::default(), height, width, "mono16", DataContainer::VecU8(data))
+}
+
+fn mono16_values(image: &CvImage) -> Vec<u16> {
+    match image.as_container() {
+        DataContainer::VecU8(data) => data.chunks_exact(2).map(|v| u16::from_le_bytes([v[0], v[1]])).collect(),
+        _ => panic!("unexpected container"),
+    }
+}
+
+#[test]
+fn decimate_area_averages_bins() {
+    let mut image = mono16_image(8, 4);
+
+    let (decimated, decimated_info) = decimate(&mut image, &camera_info(), 2, 2, Interpolation::Area).unwrap();
+
+    // First bin holds 0, 1, 8 and 9 (x100)
+    assert_eq!(mono16_values(&decimated), vec![450, 650, 850, 1050, 2050, 2250, 2450, 2650]);
+    assert_eq!((decimated_info.binning_x, decimated_info.binning_y), (2, 2));
+    assert_eq!(decimated_info.K, camera_info().K);
+
+    let model = PinholeCameraModel::from_camera_info(&decimated_info).unwrap();
+    assert_eq!(model.reduced_resolution(), (decimated.width() as u32, decimated.height() as u32));
+}
+
+#[test]
+fn decimate_nearest_keeps_top_left() {
+    let mut image = CvImage::from_parts(Header::default(), 2, 5, "32FC1", DataContainer::VecF32((0..10).map(|v| v as f32).collect()));
+
+    let (decimated, _) = decimate(&mut image, &camera_info(), 2, 1, Interpolation::Nearest).unwrap();
+
+    assert_eq!((decimated.width(), decimated.height()), (2, 2));
+    assert_eq!(*decimated.as_container(), DataContainer::VecF32(vec![0.0, 2.0, 5.0, 7.0]));
+}
+
+#[test]
+fn resize_scales_intrinsics() {
+    let mut image = mono16_image(8, 4);
+
+    let (resized, resized_info) = resize(&mut image, &camera_info(), 4, 4, Interpolation::Nearest).unwrap();
+
+    assert_eq!((resized.width(), resized.height()), (4, 4));
+    assert_eq!((resized_info.width, resized_info.height), (4, 4));
+    assert_eq!(resized_info.K, [5.0, 0.0, 2.0, 0.0, 12.0, 2.0, 0.0, 0.0, 1.0]);
+    assert_eq!(resized_info.P, [5.0, 0.0, 2.0, -0.5, 0.0, 12.0, 2.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn resize_folds_roi_and_binning() {
+    let info = CameraInfo {
+        binning_x: 2,
+        binning_y: 2,
+        roi: RegionOfInterest { x_offset: 2, y_offset: 0, width: 4, height: 4, do_rectify: false },
+        ..camera_info()
+    };
+    let mut image = mono16_image(2, 2);
+
+    let (_, resized_info) = resize(&mut image, &info, 4, 4, Interpolation::Nearest).unwrap();
+    let before = PinholeCameraModel::from_camera_info(&info).unwrap();
+    let after = PinholeCameraModel::from_camera_info(&resized_info).unwrap();
+
+    assert_eq!(resized_info.roi, RegionOfInterest::default());
+    assert_eq!((after.fx(), after.cx()), (before.fx() * 2.0, before.cx() * 2.0));
+    assert_eq!((after.fy(), after.cy()), (before.fy() * 2.0, before.cy() * 2.0));
+}
+
+#[test]
+fn resize_rejects_bayer() {
+    let mut image = CvImage::from_parts(Header::default(), 2, 2, "bayer_rggb8", DataContainer::VecU8(vec![0; 4]));
+
+    assert!(resize(&mut image, &camera_info(), 1, 1, Interpolation::Nearest).is_err());
+}
+
+#[test]
+fn short_data_fails() {
+    // One byte short of the last pixel
+    let mut image = CvImage::from_parts(Header::default(), 4, 8, "mono16", DataContainer::VecU8(vec![0; 8 * 4 * 2 - 1]));
+
+    assert!(decimate(&mut image, &camera_info(), 2, 2, Interpolation::Nearest).is_err());
+    assert!(decimate(&mut image, &camera_info(), 2, 2, Interpolation::Area).is_err());
+    assert!(resize(&mut image, &camera_info(), 4, 2, Interpolation::Nearest).is_err());
+}
@@ -0,0 +1,111 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_geometry::PinholeCameraModel,
+    image_proc::{
+        flip_horizontal,
+        flip_vertical,
+        rotate90,
+        rotate180,
+        rotate270,
+    },
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::CameraInfo,
+    },
+};
+
+const WIDTH: usize = 3;
+const HEIGHT: usize = 2;
+
+fn camera_info() -> CameraInfo {
+    CameraInfo {
+        width: WIDTH as u32,
+        height: HEIGHT as u32,
+        distortion_model: "plumb_bob".to_string(),
+        D: vec![-0.2, 0.05, 0.01, -0.02, 0.0],
+        K: [400.0, 0.0, 1.2, 0.0, 420.0, 0.7, 0.0, 0.0, 1.0],
+        R: [0.99, -0.1, 0.0, 0.1, 0.99, 0.0, 0.0, 0.0, 1.0],
+        P: [390.0, 0.0, 1.1, -39.0, 0.0, 390.0, 0.6, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn image() -> CvImage {
+    let header = Header { frame_id: "camera".to_string(), ..Default::default() };
+    CvImage::from_parts(header, HEIGHT, WIDTH, "mono8", DataContainer::VecU8(vec![0, 1, 2, 3, 4, 5]))
+}
+
+fn assert_close(a: (f64, f64), b: (f64, f64)) {
+    assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9, "{:?} != {:?}", a, b);
+}
+
+/// Checks that rectified and raw pixels of the input map onto the ones of the output
+fn assert_consistent(info: &CameraInfo, out_info: &CameraInfo, map: impl Fn((f64, f64)) -> (f64, f64)) {
+    let model = PinholeCameraModel::from_camera_info(info).unwrap();
+    let out_model = PinholeCameraModel::from_camera_info(out_info).unwrap();
+
+    for uv_rect in [(0.0, 0.0), (2.0, 1.0), (1.5, 0.25)] {
+        let uv_raw = model.unrectify_point(uv_rect);
+        assert_close(out_model.unrectify_point(map(uv_rect)), map(uv_raw));
+    }
+
+    let xyz = (0.3, -0.2, 2.0);
+    let uv = model.project_3d_to_pixel(xyz);
+    let out_uv = map(uv);
+    let ray = out_model.project_pixel_to_3d_ray(out_uv);
+    assert_close(out_model.project_3d_to_pixel(ray), out_uv);
+}
+
+#[test]
+fn rotate90_rearranges_pixels() {
+    let (rotated, rotated_info) = rotate90(&image(), &camera_info(), Some("camera_rotated")).unwrap();
+
+    assert_eq!((rotated.width(), rotated.height()), (2, 3));
+    assert_eq!(*rotated.as_container(), DataContainer::VecU8(vec![3, 0, 4, 1, 5, 2]));
+    assert_eq!((rotated_info.width, rotated_info.height), (2, 3));
+    assert_eq!(rotated.header().frame_id, "camera_rotated");
+    assert_eq!(rotated_info.header.frame_id, "camera_rotated");
+}
+
+#[test]
+fn rotate90_transforms_camera_info() {
+    let (_, rotated_info) = rotate90(&image(), &camera_info(), None).unwrap();
+
+    assert_eq!(rotated_info.K, [420.0, 0.0, HEIGHT as f64 - 1.0 - 0.7, 0.0, 400.0, 1.2, 0.0, 0.0, 1.0]);
+    assert_consistent(&camera_info(), &rotated_info, |(u, v)| (HEIGHT as f64 - 1.0 - v, u));
+}
+
+#[test]
+fn rotate180_and_270_transform_camera_info() {
+    let (rotated, rotated_info) = rotate180(&image(), &camera_info(), None).unwrap();
+    assert_eq!(*rotated.as_container(), DataContainer::VecU8(vec![5, 4, 3, 2, 1, 0]));
+    assert_eq!(rotated.header().frame_id, "camera");
+    assert_consistent(&camera_info(), &rotated_info, |(u, v)| (WIDTH as f64 - 1.0 - u, HEIGHT as f64 - 1.0 - v));
+
+    let (rotated, rotated_info) = rotate270(&image(), &camera_info(), None).unwrap();
+    assert_eq!(*rotated.as_container(), DataContainer::VecU8(vec![2, 5, 1, 4, 0, 3]));
+    assert_consistent(&camera_info(), &rotated_info, |(u, v)| (v, WIDTH as f64 - 1.0 - u));
+}
+
+#[test]
+fn flip_horizontal_transforms_camera_info() {
+    let (flipped, flipped_info) = flip_horizontal(&image(), &camera_info(), None).unwrap();
+
+    assert_eq!(*flipped.as_container(), DataContainer::VecU8(vec![2, 1, 0, 5, 4, 3]));
+    assert_consistent(&camera_info(), &flipped_info, |(u, v)| (WIDTH as f64 - 1.0 - u, v));
+}
+
+#[test]
+fn mismatched_data_length_fails() {
+    let short = CvImage::from_parts(Header::default(), HEIGHT, WIDTH, "mono8", DataContainer::VecU8(vec![0; WIDTH * HEIGHT - 1]));
+    let long = CvImage::from_parts(Header::default(), HEIGHT, WIDTH, "mono8", DataContainer::VecU8(vec![0; WIDTH * HEIGHT + 1]));
+
+    for image in [&short, &long] {
+        assert!(rotate90(image, &camera_info(), None).is_err());
+        assert!(rotate180(image, &camera_info(), None).is_err());
+        assert!(rotate270(image, &camera_info(), None).is_err());
+        assert!(flip_horizontal(image, &camera_info(), None).is_err());
+        assert!(flip_vertical(image, &camera_info(), None).is_err());
+    }
+}
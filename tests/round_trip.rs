@@ -0,0 +1,152 @@
+//! Property-based round trip tests over every encoding, both endiannesses and
+//! random sizes, row paddings and pixel data. Each encoding has its own image
+//! strategy, and proptest shrinks and prints the failing image.
+
+use proptest::{
+    prelude::*,
+    strategy::Union,
+};
+
+use cv_bridge::{
+    CvImage,
+    msgs::{
+        Time,
+        sensor_msgs::Image,
+        std_msgs::Header,
+    },
+    utils::{
+        image_encodings,
+        image_validation::bytes_per_pixel,
+    },
+};
+
+const CASES: u32 = 512;
+
+const ENCODINGS: &[&str] = &[
+    "mono8", "rgb8", "rgba8", "bgr8", "bgra8",
+    "mono16", "rgb16", "rgba16", "bgr16", "bgra16",
+    "bayer_rggb8", "bayer_bggr8", "bayer_gbrg8", "bayer_grbg8",
+    "bayer_rggb16", "bayer_bggr16", "bayer_gbrg16", "bayer_grbg16",
+    "yuv422", "yuv422_yuy2",
+    "8UC1", "8UC2", "8UC3", "8UC4",
+    "16UC1", "16UC2", "16UC3", "16UC4",
+    "32FC1", "32FC2", "32FC3", "32FC4",
+];
+
+fn header() -> impl Strategy<Value = Header> {
+    (any::<u32>(), any::<u32>(), 0..1_000_000_000u32, any::<u16>()).prop_map(|(seq, sec, nsec, camera)| Header {
+        seq,
+        stamp: Time { sec, nsec },
+        frame_id: format!("camera_{}", camera),
+    })
+}
+
+/// Strategy generating images of the encoding, with random width, height, data
+/// and endianness. Rows are padded by 1 to 7 bytes when `padded` is set.
+fn image(encoding: &'static str, padded: bool) -> impl Strategy<Value = Image> {
+    // yuv422 packs two pixels in four bytes, so the width has to be even
+    let width = match encoding.starts_with("yuv422") {
+        true => (0..=8u32).prop_map(|width| width * 2).boxed(),
+        false => (0..=17u32).boxed(),
+    };
+    let padding = match padded {
+        true => 1..=7u32,
+        false => 0..=0u32,
+    };
+
+    (width, 0..=9u32, padding, 0..=1u8, header())
+        .prop_flat_map(move |(width, height, padding, is_bigendian, header)| {
+            let step = width * bytes_per_pixel(encoding).unwrap() as u32 + padding;
+            let data = proptest::collection::vec(any::<u8>(), (step * height) as usize);
+
+            data.prop_map(move |data| Image {
+                header: header.clone(),
+                height,
+                width,
+                encoding: encoding.to_string(),
+                is_bigendian,
+                step,
+                data,
+            })
+        })
+}
+
+/// Strategy picking one of the per-encoding image strategies
+fn any_image(padded: bool) -> impl Strategy<Value = Image> {
+    Union::new(ENCODINGS.iter().map(|encoding| image(encoding, padded).boxed()))
+}
+
+/// Returns the data without the padding at the end of the rows
+fn packed_data(image: &Image) -> Vec<u8> {
+    let row_len = image.width as usize * bytes_per_pixel(&image.encoding).unwrap();
+    match image.step {
+        0 => vec![],
+        step => image.data.chunks_exact(step as usize).flat_map(|row| &row[..row_len]).copied().collect(),
+    }
+}
+
+/// Reverses the bytes of each channel value
+fn swap_bytes(data: &[u8], encoding: &str) -> Vec<u8> {
+    let bytes_per_channel = image_encodings::get_bit_depth(encoding) as usize / 8;
+    data.chunks_exact(bytes_per_channel).flat_map(|value| value.iter().rev()).copied().collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn imgmsg_round_trip_is_identity(image in any_image(false)) {
+        let is_bigendian = image.is_bigendian;
+        let cv_image = CvImage::from_imgmsg(image.clone()).unwrap();
+        let round_trip: Image = cv_image.into_imgmsg(is_bigendian).unwrap();
+
+        prop_assert_eq!(round_trip, image);
+    }
+
+    #[test]
+    fn imgmsg_round_trip_removes_row_padding(image in any_image(true)) {
+        let cv_image = CvImage::from_imgmsg(image.clone()).unwrap();
+        let round_trip: Image = cv_image.into_imgmsg(image.is_bigendian).unwrap();
+
+        prop_assert_eq!(round_trip.step, image.width * bytes_per_pixel(&image.encoding).unwrap() as u32);
+        prop_assert_eq!(round_trip.data, packed_data(&image));
+        prop_assert_eq!(round_trip.header, image.header);
+    }
+
+    #[test]
+    fn imgmsg_endianness_conversion(image in any_image(false)) {
+        let little: Image = CvImage::from_imgmsg(image.clone()).unwrap().into_imgmsg(0).unwrap();
+        let big: Image = CvImage::from_imgmsg(image.clone()).unwrap().into_imgmsg(1).unwrap();
+
+        prop_assert_eq!(big.data, swap_bytes(&little.data, &image.encoding));
+
+        // Converting the message to the other byte order and back does not change it
+        let swapped: Image = CvImage::from_imgmsg(image.clone()).unwrap().into_imgmsg(1 - image.is_bigendian).unwrap();
+        let restored: Image = CvImage::from_imgmsg(swapped).unwrap().into_imgmsg(image.is_bigendian).unwrap();
+        prop_assert_eq!(restored, image);
+    }
+}
+
+#[cfg(feature = "opencv")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn cvmat_round_trip_preserves_pixels(image in any_image(false)) {
+        use opencv::prelude::*;
+
+        prop_assume!(image.width != 0 && image.height != 0);
+
+        let mut cv_image = CvImage::from_imgmsg(image.clone()).unwrap();
+        let mat = cv_image.as_cvmat().unwrap();
+        prop_assert_eq!((mat.cols(), mat.rows()), (image.width as i32, image.height as i32));
+
+        let mut from_mat = CvImage::from_cvmat(mat.try_clone().unwrap(), &image.encoding).unwrap();
+        let round_trip_mat = from_mat.as_cvmat().unwrap();
+        prop_assert_eq!(round_trip_mat.data_bytes().unwrap(), mat.data_bytes().unwrap());
+
+        *from_mat.header_mut() = image.header.clone();
+        let round_trip: Image = from_mat.into_imgmsg(image.is_bigendian).unwrap();
+        prop_assert_eq!(round_trip, image);
+    }
+}
@@ -0,0 +1,95 @@
+use cv_bridge::{
+    CvImage,
+    cv_image::DataContainer,
+    image_geometry::StereoCameraModel,
+    msgs::{
+        std_msgs::Header,
+        sensor_msgs::CameraInfo,
+    },
+};
+
+fn camera_info(tx: f64) -> CameraInfo {
+    CameraInfo {
+        width: 640,
+        height: 480,
+        K: [500.0, 0.0, 320.0, 0.0, 500.0, 240.0, 0.0, 0.0, 1.0],
+        R: [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+        P: [500.0, 0.0, 320.0, tx, 0.0, 500.0, 240.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+        ..Default::default()
+    }
+}
+
+fn stereo_model() -> StereoCameraModel {
+    // 10cm baseline
+    StereoCameraModel::from_camera_info(&camera_info(0.0), &camera_info(-50.0)).unwrap()
+}
+
+#[test]
+fn disparity_and_depth_are_inverse() {
+    let model = stereo_model();
+    assert!((model.baseline() - 0.1).abs() < 1e-12);
+
+    for z in [0.5, 1.0, 2.5, 10.0] {
+        let disparity = model.get_disparity(z);
+        assert!((model.get_z(disparity) - z).abs() < 1e-9);
+
+        let (x, y, z3d) = model.project_disparity_to_3d((420.0, 140.0), disparity);
+        assert!((z3d - z).abs() < 1e-9);
+        assert!((x - 100.0 * z / 500.0).abs() < 1e-9);
+        assert!((y + 100.0 * z / 500.0).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn disparity_image_to_depth() {
+    let model = stereo_model();
+    let header = Header {
+        frame_id: "left_optical".to_string(),
+        ..Default::default()
+    };
+
+    let disparity = vec![50.0, 25.0, 0.0, f32::NAN];
+    let disparity = CvImage::from_parts(header, 2, 2, "32FC1", DataContainer::VecF32(disparity));
+
+    let depth = model.disparity_image_to_depth(&disparity).unwrap();
+    assert_eq!(depth.encoding(), "32FC1");
+    assert_eq!(depth.header().frame_id, "left_optical");
+
+    match depth.as_container() {
+        DataContainer::VecF32(data) => {
+            assert!((data[0] - 1.0).abs() < 1e-6);
+            assert!((data[1] - 2.0).abs() < 1e-6);
+            assert!(data[2].is_nan());
+            assert!(data[3].is_nan());
+        },
+        container => panic!("unexpected container {:?}", container),
+    }
+}
+
+#[test]
+fn missing_baseline_fails() {
+    assert!(StereoCameraModel::from_camera_info(&camera_info(0.0), &camera_info(0.0)).is_err());
+}
+
+#[test]
+fn disparity_image_to_depth_with_principal_point_offset() {
+    // The right principal point is 10 pixels left of the left one, so the
+    // disparity of points at infinity is 10
+    let mut right = camera_info(-50.0);
+    right.P[2] = 310.0;
+    let model = StereoCameraModel::from_camera_info(&camera_info(0.0), &right).unwrap();
+
+    let disparity = vec![60.0, 10.0, 5.0, f32::INFINITY];
+    let disparity = CvImage::from_parts(Header::default(), 2, 2, "32FC1", DataContainer::VecF32(disparity));
+
+    let depth = model.disparity_image_to_depth(&disparity).unwrap();
+    match depth.as_container() {
+        DataContainer::VecF32(data) => {
+            assert!((data[0] - 1.0).abs() < 1e-6);
+            assert!(data[1].is_nan());
+            assert!(data[2].is_nan());
+            assert!(data[3].is_nan());
+        },
+        container => panic!("unexpected container {:?}", container),
+    }
+}